@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use proc_macro2::Ident;
 use syn::{parse::Parse, Token};
@@ -18,7 +18,6 @@ use syn::{parse::Parse, Token};
 /// Step 6: Populate the edges of the graph with the previously processed data and return the graph.
 ///
 /// This code provides the logic to dynamically build a graph based on parsed task relationships, where each task is a node and the successors define directed edges between nodes.
-
 pub(crate) struct Relay {
     pub(crate) task: Ident,
     pub(crate) successors: Vec<Ident>,
@@ -77,14 +76,14 @@ pub(crate) fn add_relay(relaies: Relaies) -> proc_macro2::TokenStream {
     }
     for relay in relaies.0.iter() {
         let task = relay.task.clone();
-        if (!cache.contains(&task)) {
+        if !cache.contains(&task) {
             token.extend(quote::quote!(
                 graph.add_node(Box::new(#task));
             ));
             cache.insert(task);
         }
         for successor in relay.successors.iter() {
-            if (!cache.contains(successor)) {
+            if !cache.contains(successor) {
                 token.extend(quote::quote!(
                     graph.add_node(Box::new(#successor));
                 ));
@@ -1,10 +1,4 @@
-use std::sync::Arc;
-
-use dagrs::{
-    auto_node, dependencies,
-    graph::{self, graph::Graph},
-    EmptyAction, EnvVar, InChannels, Node, NodeTable, OutChannels,
-};
+use dagrs::{auto_node, dependencies, EmptyAction, InChannels, Node, NodeTable, OutChannels};
 
 #[auto_node]
 struct MyNode {/*Put customized fields here.*/}
@@ -41,5 +35,5 @@ fn main() {
      b -> a
     );
 
-    g.run();
+    g.start().unwrap();
 }
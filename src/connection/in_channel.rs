@@ -4,46 +4,119 @@ use tokio::sync::{broadcast, mpsc, Mutex};
 
 use crate::node::node::NodeId;
 
-use super::information_packet::Content;
+use super::{information_packet::Content, permit::RunPermit};
 
 /// # Input Channels
 /// A hash-table mapping `NodeId` to `InChannel`. In **Dagrs**, each `Node` stores input
 /// channels in this map, enabling `Node` to receive information packets from other `Node`s.
+///
+/// When the parallelism of the graph is limited, a node waiting to receive a content does not
+/// count as running, except with [`InChannels::blocking_recv_from`].
+///
+/// The contents received by a node that may be retried are kept, and received again by the
+/// next attempt before the new ones.
 #[derive(Default)]
-pub struct InChannels(pub(crate) HashMap<NodeId, Arc<Mutex<InChannel>>>);
+pub struct InChannels {
+    pub(crate) channels: HashMap<NodeId, Arc<Mutex<InChannel>>>,
+    /// The permit of the node to run, if the parallelism is limited.
+    permit: Option<RunPermit>,
+    /// The contents received so far, if the node may be retried.
+    replay: Option<Replay>,
+}
 
 impl InChannels {
     /// Perform a blocking receive on the incoming channel from `NodeId`.
     pub fn blocking_recv_from(&mut self, id: &NodeId) -> Result<Content, RecvErr> {
-        match self.get(id) {
+        if let Some(content) = self.replay.as_mut().and_then(|replay| replay.next(id)) {
+            return Ok(content);
+        }
+        let result = match self.get(id) {
             Some(channel) => channel.blocking_lock().blocking_recv(),
             None => Err(RecvErr::NoSuchChannel),
-        }
+        };
+        self.keep(id, result)
     }
     /// Perform a asynchronous receive on the incoming channel from `NodeId`.
     pub async fn recv_from(&mut self, id: &NodeId) -> Result<Content, RecvErr> {
-        match self.get(id) {
-            Some(channel) => channel.lock().await.recv().await,
+        if let Some(content) = self.replay.as_mut().and_then(|replay| replay.next(id)) {
+            return Ok(content);
+        }
+        let result = match self.get(id) {
+            Some(channel) => {
+                RunPermit::idle(self.permit.as_ref(), async {
+                    channel.lock().await.recv().await
+                })
+                .await
+            }
             None => Err(RecvErr::NoSuchChannel),
+        };
+        self.keep(id, result)
+    }
+
+    /// Keep the received content for the next attempt, if the node may be retried.
+    fn keep(&mut self, id: &NodeId, result: Result<Content, RecvErr>) -> Result<Content, RecvErr> {
+        if let (Some(replay), Ok(content)) = (&mut self.replay, &result) {
+            replay.push(*id, content.clone());
         }
+        result
     }
 
     /// Close the channel by the given `NodeId`, and remove the channel in this map.
     pub fn close(&mut self, id: &NodeId) {
         if let Some(c) = self.get(id) {
             c.blocking_lock().close();
-            self.0.remove(id);
+            self.channels.remove(id);
         }
     }
 
-    fn get(&self, id: &NodeId) -> Option<Arc<Mutex<InChannel>>> {
-        match self.0.get(id) {
-            Some(c) => Some(c.clone()),
-            None => None,
+    /// Set the permit of the node to run, given back while the node waits for a content.
+    pub(crate) fn set_permit(&mut self, permit: Option<RunPermit>) {
+        self.permit = permit;
+    }
+
+    /// Keep the contents received from now on, so that [`InChannels::rewind`] receives them
+    /// again, or stop keeping them.
+    pub(crate) fn set_replay(&mut self, on: bool) {
+        self.replay = on.then(Replay::default);
+    }
+
+    /// Receive the kept contents again, before the new ones, for the next attempt.
+    pub(crate) fn rewind(&mut self) {
+        if let Some(replay) = &mut self.replay {
+            replay.next.clear();
         }
     }
+
+    fn get(&self, id: &NodeId) -> Option<Arc<Mutex<InChannel>>> {
+        self.channels.get(id).cloned()
+    }
     pub fn insert(&mut self, node_id: NodeId, channel: Arc<Mutex<InChannel>>) {
-        self.0.insert(node_id, channel);
+        self.channels.insert(node_id, channel);
+    }
+}
+
+/// The contents received by a node from each predecessor, and the position of the next one
+/// to receive again.
+#[derive(Default)]
+struct Replay {
+    received: HashMap<NodeId, Vec<Content>>,
+    next: HashMap<NodeId, usize>,
+}
+
+impl Replay {
+    /// Get the next kept content from `id`, if it has not been received again yet.
+    fn next(&mut self, id: &NodeId) -> Option<Content> {
+        let next = self.next.entry(*id).or_default();
+        let content = self.received.get(id)?.get(*next)?.clone();
+        *next += 1;
+        Some(content)
+    }
+
+    /// Keep a content newly received from `id`.
+    fn push(&mut self, id: NodeId, content: Content) {
+        let received = self.received.entry(id).or_default();
+        received.push(content);
+        self.next.insert(id, received.len());
     }
 }
 
@@ -113,7 +186,7 @@ impl InChannel {
 /// - NoSuchChannel: try to get a channel with an invalid `NodeId`.
 /// - Closed: the channel to receive messages from is closed and empty already.
 /// - Lagged(x): the channel encounters a cache overflow and `x` information
-/// pakages are dropped on this receiver's side.
+///   pakages are dropped on this receiver's side.
 #[derive(Debug)]
 pub enum RecvErr {
     NoSuchChannel,
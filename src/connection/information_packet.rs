@@ -1,9 +1,28 @@
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::HashMap, mem::size_of, sync::Arc};
 
 /// Container type to store task output.
 #[derive(Debug, Clone)]
 pub struct Content {
     inner: Arc<dyn Any + Send + Sync>,
+    /// The estimated size in bytes, if the content is constructed with [`Content::sized`].
+    size: Option<usize>,
+}
+
+/// Estimate the size in bytes of a value stored in a [`Content`], see [`Content::sized`].
+pub trait ContentSize {
+    fn content_size(&self) -> usize;
+}
+
+impl ContentSize for String {
+    fn content_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> ContentSize for Vec<T> {
+    fn content_size(&self) -> usize {
+        self.len() * size_of::<T>()
+    }
 }
 
 impl Content {
@@ -11,11 +30,33 @@ impl Content {
     pub fn new<H: Send + Sync + 'static>(val: H) -> Self {
         Self {
             inner: Arc::new(val),
+            size: None,
+        }
+    }
+
+    /// Construct a new [`Content`] whose size is known, so that it can be checked against
+    /// the maximum output size of a node.
+    pub fn sized<H: ContentSize + Send + Sync + 'static>(val: H) -> Self {
+        let size = val.content_size();
+        Self {
+            inner: Arc::new(val),
+            size: Some(size),
+        }
+    }
+
+    /// Construct a new [`Content`] of the given estimated size.
+    pub(crate) fn with_size<H: Send + Sync + 'static>(val: H, size: Option<usize>) -> Self {
+        Self {
+            inner: Arc::new(val),
+            size,
         }
     }
 
     pub fn from_arc<H: Send + Sync + 'static>(val: Arc<H>) -> Self {
-        Self { inner: val }
+        Self {
+            inner: val,
+            size: None,
+        }
     }
 
     pub fn get<H: 'static>(&self) -> Option<&H> {
@@ -25,4 +66,37 @@ impl Content {
     pub fn into_inner<H: Send + Sync + 'static>(self) -> Option<Arc<H>> {
         self.inner.downcast::<H>().ok()
     }
+
+    /// Get an owned clone of the value, `None` if it is not of type `H`.
+    ///
+    /// ```rust
+    /// use dagrs::Content;
+    ///
+    /// let content = Content::new(vec![1, 2, 3]);
+    /// assert_eq!(content.try_clone_as::<Vec<i32>>(), Some(vec![1, 2, 3]));
+    /// assert_eq!(content.try_clone_as::<String>(), None);
+    /// ```
+    pub fn try_clone_as<H: Clone + 'static>(&self) -> Option<H> {
+        self.get::<H>().cloned()
+    }
+
+    /// Get the value named `key`, if this content is the map of named contents sent or
+    /// output for an `Output::Map`. `None` if there is no such value of type `H`.
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use dagrs::Content;
+    ///
+    /// let content = Content::new(HashMap::from([("count".to_string(), Content::new(3usize))]));
+    /// assert_eq!(content.get_named::<usize>("count"), Some(&3));
+    /// assert_eq!(content.get_named::<usize>("label"), None);
+    /// ```
+    pub fn get_named<H: 'static>(&self, key: &str) -> Option<&H> {
+        self.get::<HashMap<String, Content>>()?.get(key)?.get()
+    }
+
+    /// The estimated size in bytes, `None` if it is unknown.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
 }
@@ -1,3 +1,4 @@
 pub mod in_channel;
 pub mod information_packet;
 pub mod out_channel;
+pub(crate) mod permit;
@@ -1,50 +1,172 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex},
+};
 
 use tokio::sync::{broadcast, mpsc};
 
 use crate::node::node::NodeId;
 
-use super::information_packet::Content;
+use super::{information_packet::Content, permit::RunPermit};
 
 /// # Output Channels
 /// A hash-table mapping `NodeId` to `OutChannel`. In **Dagrs**, each `Node` stores output
 /// channels in this map, enabling `Node` to send information packets to other `Node`s.
+///
+/// The contents whose size is known, see [`Content::sized`], are not sent if they are larger
+/// than the maximum output size of the node, and [`SendErr::TooLarge`] is returned instead.
+///
+/// When the parallelism of the graph is limited, a node waiting for a full channel to send a
+/// content does not count as running, except with [`OutChannels::blocking_send_to`].
+///
+/// The contents sent by a node that may be retried are held, and only sent once an attempt
+/// succeeds, so that the successors receive the contents of that attempt only.
 #[derive(Default)]
-pub struct OutChannels(pub(crate) HashMap<NodeId, Arc<OutChannel>>);
+pub struct OutChannels {
+    pub(crate) channels: HashMap<NodeId, Arc<OutChannel>>,
+    /// The maximum size in bytes of the contents sent, if any.
+    max_size: Option<usize>,
+    /// The permit of the node to run, if the parallelism is limited.
+    permit: Option<RunPermit>,
+    /// The contents held, if the node may be retried.
+    held: Option<Mutex<Held>>,
+}
+
+/// The contents held with their receiver, or `None` for all the successors.
+type Held = Vec<(Option<NodeId>, Content)>;
 
 impl OutChannels {
     /// Perform a blocking send on the outcoming channel from `NodeId`.
     pub fn blocking_send_to(&self, id: &NodeId, content: Content) -> Result<(), SendErr> {
+        self.check_size(&content)?;
         match self.get(id) {
-            Some(channel) => channel.blocking_send(content),
+            Some(channel) => match self.hold(Some(*id), content) {
+                Some(content) => channel.blocking_send(content),
+                None => Ok(()),
+            },
             None => Err(SendErr::NoSuchChannel),
         }
     }
 
     /// Perform a asynchronous send on the outcoming channel from `NodeId`.
     pub async fn send_to(&self, id: &NodeId, content: Content) -> Result<(), SendErr> {
+        self.check_size(&content)?;
         match self.get(id) {
-            Some(channel) => channel.send(content).await,
+            Some(channel) => match self.hold(Some(*id), content) {
+                Some(content) => RunPermit::idle(self.permit.as_ref(), channel.send(content)).await,
+                None => Ok(()),
+            },
             None => Err(SendErr::NoSuchChannel),
         }
     }
 
     /// Close the channel by the given `NodeId`, and remove the channel in this map.
     pub fn close(&mut self, id: &NodeId) {
-        if let Some(_) = self.get(id) {
-            self.0.remove(id);
+        if self.get(id).is_some() {
+            self.channels.remove(id);
         }
     }
 
-    fn get(&self, id: &NodeId) -> Option<Arc<OutChannel>> {
-        match self.0.get(id) {
-            Some(c) => Some(c.clone()),
-            None => None,
+    /// Send the content to all the successors. A broadcast channel shared by several
+    /// successors is sent to only once.
+    pub(crate) async fn send_to_all(&self, content: Content) -> Result<(), SendErr> {
+        self.check_size(&content)?;
+        match self.hold(None, content) {
+            Some(content) => self.deliver_to_all(content).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Send the content to all the successors at once, see [`OutChannels::send_to_all`].
+    async fn deliver_to_all(&self, content: Content) -> Result<(), SendErr> {
+        let mut sent: Vec<&broadcast::Sender<Content>> = Vec::new();
+        for channel in self.channels.values() {
+            if let OutChannel::Bcst(sender) = channel.as_ref() {
+                if sent.iter().any(|s| s.same_channel(sender)) {
+                    continue;
+                }
+                sent.push(sender);
+            }
+            RunPermit::idle(self.permit.as_ref(), channel.send(content.clone())).await?;
+        }
+        Ok(())
+    }
+
+    /// Close all the channels, and remove them from this map.
+    pub(crate) fn close_all(&mut self) {
+        self.channels.clear();
+    }
+
+    /// Limit the size of the contents sent to `max` bytes, or remove the limit.
+    pub(crate) fn set_max_size(&mut self, max: Option<usize>) {
+        self.max_size = max;
+    }
+
+    /// Set the permit of the node to run, given back while the node waits for a full channel.
+    pub(crate) fn set_permit(&mut self, permit: Option<RunPermit>) {
+        self.permit = permit;
+    }
+
+    /// Hold the contents sent from now on until [`OutChannels::send_held`], or stop holding
+    /// them.
+    pub(crate) fn set_held(&mut self, on: bool) {
+        self.held = on.then(Mutex::default);
+    }
+
+    /// Hold the content sent to `id`, or to all the successors, if the sends are held.
+    /// Otherwise the content is given back to be sent at once.
+    fn hold(&self, id: Option<NodeId>, content: Content) -> Option<Content> {
+        match &self.held {
+            Some(held) => {
+                held.lock().unwrap().push((id, content));
+                None
+            }
+            None => Some(content),
         }
     }
 
+    /// Drop the contents held, sent by a failed attempt.
+    pub(crate) fn discard_held(&self) {
+        if let Some(held) = &self.held {
+            held.lock().unwrap().clear();
+        }
+    }
+
+    /// Send the contents held, in the order they were sent by the node.
+    pub(crate) async fn send_held(&self) -> Result<(), SendErr> {
+        let held = match &self.held {
+            Some(held) => mem::take(&mut *held.lock().unwrap()),
+            None => return Ok(()),
+        };
+        for (id, content) in held {
+            match id {
+                Some(id) => match self.get(&id) {
+                    Some(channel) => {
+                        RunPermit::idle(self.permit.as_ref(), channel.send(content)).await?
+                    }
+                    None => return Err(SendErr::NoSuchChannel),
+                },
+                None => self.deliver_to_all(content).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the content is not larger than the maximum size.
+    fn check_size(&self, content: &Content) -> Result<(), SendErr> {
+        match (content.size(), self.max_size) {
+            (Some(size), Some(max)) if size > max => Err(SendErr::TooLarge(size)),
+            _ => Ok(()),
+        }
+    }
+
+    fn get(&self, id: &NodeId) -> Option<Arc<OutChannel>> {
+        self.channels.get(id).cloned()
+    }
+
     pub fn insert(&mut self, node_id: NodeId, channel: Arc<OutChannel>) {
-        self.0.insert(node_id, channel);
+        self.channels.insert(node_id, channel);
     }
 }
 
@@ -92,6 +214,8 @@ impl OutChannel {
 /// # Output Channel Error Types
 /// - NoSuchChannel: try to get a channel with an invalid `NodeId`.
 /// - ClosedChannel: the channel is closed alredy.
+/// - TooLarge(size): the content has `size` bytes, more than the maximum output size of the
+///   node sending it.
 ///
 /// In cases of getting errs of type `MpscError` and `BcstError`, the sender
 /// will find there are no active receivers left, so try to send messages is
@@ -100,4 +224,5 @@ impl OutChannel {
 pub enum SendErr {
     NoSuchChannel,
     ClosedChannel(Content),
+    TooLarge(usize),
 }
@@ -0,0 +1,58 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The permit of a node to run, when the parallelism of the graph is limited.
+///
+/// A node only holds its permit while it runs, and gives it back while it waits to send or
+/// receive a content through its channels, so that it never keeps the nodes it waits for
+/// from running.
+#[derive(Clone)]
+pub(crate) struct RunPermit {
+    semaphore: Arc<Semaphore>,
+    permit: Arc<Mutex<Option<OwnedSemaphorePermit>>>,
+}
+
+impl RunPermit {
+    pub(crate) fn new(semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            semaphore,
+            permit: Arc::default(),
+        }
+    }
+
+    /// Wait until the permit is got.
+    pub(crate) async fn acquire(&self) {
+        let permit = self.semaphore.clone().acquire_owned().await.unwrap();
+        *self.permit.lock().unwrap() = Some(permit);
+    }
+
+    /// Give the permit back, and return whether it was held.
+    pub(crate) fn release(&self) -> bool {
+        self.permit.lock().unwrap().take().is_some()
+    }
+
+    /// Wait for `fut`. If `fut` does not complete at once, the permit is given back meanwhile,
+    /// and got again afterwards if it was held.
+    pub(crate) async fn idle<F: Future>(permit: Option<&Self>, fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let permit = match permit {
+            Some(permit) => permit,
+            None => return fut.await,
+        };
+        if let Poll::Ready(out) = poll_fn(|cx| Poll::Ready(fut.as_mut().poll(cx))).await {
+            return out;
+        }
+        let held = permit.release();
+        let out = fut.await;
+        if held {
+            permit.acquire().await;
+        }
+        out
+    }
+}
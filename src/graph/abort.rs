@@ -0,0 +1,74 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use tokio::sync::watch;
+
+/// # Graph abort handle
+///
+/// A [`GraphAbortHandle`] cancels a running [`Graph`](super::graph::Graph) from outside,
+/// e.g. from another thread while `Graph::start` blocks. It is got with
+/// `Graph::abort_handle`, and can be cloned and sent to other threads.
+///
+/// Once aborted, the nodes still running are cancelled, so their outputs are not sent to
+/// their successors, and the run returns `Ok(false)`. The graph must be reset before it can
+/// be started again.
+#[derive(Clone)]
+pub struct GraphAbortHandle {
+    is_active: Arc<AtomicBool>,
+    aborted: Arc<watch::Sender<bool>>,
+}
+
+impl GraphAbortHandle {
+    pub(crate) fn new(is_active: Arc<AtomicBool>, aborted: Arc<watch::Sender<bool>>) -> Self {
+        Self { is_active, aborted }
+    }
+
+    /// Abort the graph. Aborting a graph that is not running stops its next run.
+    pub fn abort(&self) {
+        self.is_active.store(false, Ordering::Relaxed);
+        self.aborted.send_replace(true);
+    }
+
+    /// Whether the graph has been aborted since it was last reset.
+    pub fn is_aborted(&self) -> bool {
+        *self.aborted.borrow()
+    }
+}
+
+/// A future that completes with `None` as soon as the graph is aborted. An inner future
+/// completing in the same poll keeps its output.
+pub(crate) struct Abortable<F> {
+    inner: Pin<Box<F>>,
+    aborted: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+impl<F: Future> Abortable<F> {
+    pub(crate) fn new(inner: F, mut aborted: watch::Receiver<bool>) -> Self {
+        Self {
+            inner: Box::pin(inner),
+            aborted: Box::pin(async move {
+                if aborted.wait_for(|aborted| *aborted).await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }),
+        }
+    }
+}
+
+impl<F: Future> Future for Abortable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.aborted.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        self.inner.as_mut().poll(cx).map(Some)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    node::{
+        action::Action,
+        default_node::DefaultNode,
+        node::{Node, NodeId, NodeName, NodeTable},
+    },
+    utils::env::EnvVar,
+};
+
+use super::{error::GraphError, graph::Graph};
+
+/// # Graph builder
+///
+/// A [`GraphBuilder`] creates the nodes of a [`Graph`] first, and wires their dependencies by
+/// the ids it returns afterwards, so that no node has to be held while the graph is built.
+/// It is got with `Graph::builder`.
+///
+/// The graph built gets the environment variables holding the table of its nodes, so that
+/// actions can look up the ids of the nodes by their names.
+///
+/// ```rust
+/// use dagrs::{EmptyAction, Graph};
+///
+/// let mut builder = Graph::builder();
+/// let a = builder.add_node("Node A", Box::new(EmptyAction));
+/// let b = builder.add_node("Node B", Box::new(EmptyAction));
+/// builder.add_dependency(a, b);
+/// let mut graph = builder.build().unwrap();
+/// assert!(graph.start().unwrap());
+/// ```
+#[derive(Default)]
+pub struct GraphBuilder {
+    nodes: Vec<Box<dyn Node>>,
+    node_table: NodeTable,
+    dependencies: Vec<(NodeId, NodeId)>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a [`DefaultNode`] running `action`, and get its id.
+    pub fn add_node(&mut self, name: impl Into<NodeName>, action: Box<dyn Action>) -> NodeId {
+        let node = DefaultNode::with_action(name.into(), action, &mut self.node_table);
+        self.add_boxed_node(|_| Box::new(node))
+    }
+
+    /// Add the node created by `f` with the node table of the graph, and get its id. This
+    /// adds the nodes of other types, or the [`DefaultNode`]s configured further.
+    pub fn add_boxed_node(&mut self, f: impl FnOnce(&mut NodeTable) -> Box<dyn Node>) -> NodeId {
+        let node = f(&mut self.node_table);
+        let id = node.id();
+        self.nodes.push(node);
+        id
+    }
+
+    /// Make the node `to` depend on the node `from`, i.e. add an edge from `from` to `to`.
+    pub fn add_dependency(&mut self, from: NodeId, to: NodeId) -> &mut Self {
+        self.dependencies.push((from, to));
+        self
+    }
+
+    /// Build the graph. Returns [`GraphError::UnknownNode`] if a dependency leads to or from
+    /// a node not added to this builder, or [`GraphError::DuplicateId`] if several nodes
+    /// added have the same id.
+    pub fn build(self) -> Result<Graph, GraphError> {
+        let ids: Vec<NodeId> = self.nodes.iter().map(|node| node.id()).collect();
+        if let Some(id) = self
+            .dependencies
+            .iter()
+            .flat_map(|(from, to)| [from, to])
+            .find(|id| !ids.contains(id))
+        {
+            return Err(GraphError::UnknownNode(*id));
+        }
+        let mut graph = Graph::try_with_nodes(self.nodes)?;
+        // The successors of each node are added at once, in the order they are given.
+        let mut edges: Vec<(NodeId, Vec<NodeId>)> = Vec::new();
+        for (from, to) in self.dependencies {
+            match edges.iter_mut().find(|(id, _)| *id == from) {
+                Some((_, to_ids)) => to_ids.push(to),
+                None => edges.push((from, vec![to])),
+            }
+        }
+        for (from, to_ids) in edges {
+            graph.add_edge(from, to_ids);
+        }
+        graph.set_env(EnvVar::new(self.node_table));
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod test_builder {
+
+    use std::sync::Arc;
+
+    use crate::{
+        Action, Content, DefaultNode, EmptyAction, EnvVar, GraphError, InChannels, NodeId,
+        NodeName, OutChannels, Output,
+    };
+
+    use super::GraphBuilder;
+
+    use async_trait::async_trait;
+
+    /// An implementation of [`Action`] that sums the numbers received from all its
+    /// predecessors plus one, and sends the sum to its successors.
+    pub struct IncAction;
+    #[async_trait]
+    impl Action for IncAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let mut sum = 1;
+            let predecessors: Vec<NodeId> = in_channels.channels.keys().copied().collect();
+            for id in predecessors {
+                sum += *in_channels
+                    .recv_from(&id)
+                    .await
+                    .unwrap()
+                    .get::<i32>()
+                    .unwrap();
+            }
+            out_channels.send_to_all(Content::new(sum)).await.unwrap();
+            Output::new(sum)
+        }
+    }
+
+    /// Test for building a graph by the ids of its nodes.
+    ///
+    /// Node A precedes Node B and Node C, which both precede Node D, so Node D sums 5.
+    #[test]
+    fn build_graph() {
+        let mut builder = GraphBuilder::new();
+        let a = builder.add_node("Node A", Box::new(IncAction));
+        let b = builder.add_node("Node B", Box::new(IncAction));
+        let c = builder.add_boxed_node(|node_table| {
+            Box::new(DefaultNode::with_action(
+                NodeName::from("Node C"),
+                Box::new(IncAction),
+                node_table,
+            ))
+        });
+        let d = builder.add_node("Node D", Box::new(IncAction));
+        builder
+            .add_dependency(a, b)
+            .add_dependency(a, c)
+            .add_dependency(b, d)
+            .add_dependency(c, d);
+        let mut graph = builder.build().unwrap();
+
+        assert!(graph.start().unwrap());
+        assert_eq!(*graph.get_result::<i32>(&d).unwrap(), 5);
+    }
+
+    /// Test for a dependency on a node not added to the builder.
+    #[test]
+    fn build_unknown_node() {
+        let mut builder = GraphBuilder::new();
+        let a = builder.add_node("Node A", Box::new(EmptyAction));
+        let unknown = NodeId(usize::MAX);
+        builder.add_dependency(a, unknown);
+        assert!(matches!(builder.build(), Err(GraphError::UnknownNode(id)) if id == unknown));
+    }
+}
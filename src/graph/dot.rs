@@ -0,0 +1,264 @@
+use std::{iter::Peekable, vec::IntoIter};
+
+use super::error::GraphError;
+
+/// A digraph parsed from the Graphviz DOT format, see `Graph::from_dot`.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Dot {
+    /// The ids of the nodes with their labels, in the order they first appear.
+    pub(crate) nodes: Vec<(String, Option<String>)>,
+    /// The edges between the ids of the nodes, in the order they appear.
+    pub(crate) edges: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier, a number or a quoted string, unquoted.
+    Id(String),
+    Symbol(&'static str),
+}
+
+/// Split `src` into tokens, skipping the whitespaces and the comments.
+fn tokenize(src: &str) -> Result<Vec<Token>, GraphError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => {
+                chars.find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => last = c,
+                        None => return Err(invalid("unterminated comment")),
+                    }
+                }
+            }
+            '{' => tokens.push(Token::Symbol("{")),
+            '}' => tokens.push(Token::Symbol("}")),
+            '[' => tokens.push(Token::Symbol("[")),
+            ']' => tokens.push(Token::Symbol("]")),
+            ';' => tokens.push(Token::Symbol(";")),
+            ',' => tokens.push(Token::Symbol(",")),
+            '=' => tokens.push(Token::Symbol("=")),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Symbol("->"));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                return Err(invalid("undirected edges are not allowed in a digraph"));
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some('\n') => {}
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(invalid("unterminated string")),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err(invalid("unterminated string")),
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    id.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Id(id));
+            }
+            c => return Err(invalid(&format!("unexpected character '{}'", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+fn invalid(reason: &str) -> GraphError {
+    GraphError::InvalidDot(reason.to_string())
+}
+
+/// Take the next token, which must be an id.
+fn next_id(tokens: &mut Peekable<IntoIter<Token>>) -> Result<String, GraphError> {
+    match tokens.next() {
+        Some(Token::Id(id)) => Ok(id),
+        Some(Token::Symbol(symbol)) => Err(invalid(&format!("unexpected '{}'", symbol))),
+        None => Err(invalid("unexpected end")),
+    }
+}
+
+/// Parse a digraph in the DOT format. Only the nodes, their labels and the edges are kept,
+/// the other attributes are ignored. Subgraphs and ports are not supported.
+pub(crate) fn parse(src: &str) -> Result<Dot, GraphError> {
+    let tokens = tokenize(src)?;
+    let mut tokens = tokens.into_iter().peekable();
+
+    if matches!(tokens.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case("strict")) {
+        tokens.next();
+    }
+    match tokens.next() {
+        Some(Token::Id(id)) if id.eq_ignore_ascii_case("digraph") => {}
+        _ => return Err(invalid("expected a digraph")),
+    }
+    if matches!(tokens.peek(), Some(Token::Id(_))) {
+        tokens.next();
+    }
+    if tokens.next() != Some(Token::Symbol("{")) {
+        return Err(invalid("expected '{'"));
+    }
+
+    let mut dot = Dot::default();
+    loop {
+        let id = match tokens.next() {
+            Some(Token::Symbol("}")) => break,
+            Some(Token::Symbol(";")) => continue,
+            Some(Token::Id(id)) => id,
+            Some(Token::Symbol(symbol)) => {
+                return Err(invalid(&format!("unexpected '{}'", symbol)))
+            }
+            None => return Err(invalid("expected '}'")),
+        };
+        if id.eq_ignore_ascii_case("subgraph") {
+            return Err(invalid("subgraphs are not supported"));
+        }
+        // A graph attribute, e.g. `rankdir = LR`.
+        if tokens.peek() == Some(&Token::Symbol("=")) {
+            tokens.next();
+            next_id(&mut tokens)?;
+            continue;
+        }
+        let mut chain = vec![id];
+        while tokens.peek() == Some(&Token::Symbol("->")) {
+            tokens.next();
+            chain.push(next_id(&mut tokens)?);
+        }
+        let mut label = None;
+        if tokens.peek() == Some(&Token::Symbol("[")) {
+            tokens.next();
+            loop {
+                match tokens.next() {
+                    Some(Token::Symbol("]")) => break,
+                    Some(Token::Symbol(",")) | Some(Token::Symbol(";")) => continue,
+                    Some(Token::Id(key)) => {
+                        if tokens.next() != Some(Token::Symbol("=")) {
+                            return Err(invalid(&format!("expected '=' after '{}'", key)));
+                        }
+                        let value = next_id(&mut tokens)?;
+                        if key == "label" {
+                            label = Some(value);
+                        }
+                    }
+                    _ => return Err(invalid("expected ']'")),
+                }
+            }
+        }
+        let is_default = chain.len() == 1
+            && ["node", "edge", "graph"]
+                .iter()
+                .any(|keyword| chain[0].eq_ignore_ascii_case(keyword));
+        if is_default {
+            continue;
+        }
+        for id in &chain {
+            if !dot.nodes.iter().any(|(node, _)| node == id) {
+                dot.nodes.push((id.clone(), None));
+            }
+        }
+        if chain.len() == 1 {
+            if let Some(label) = label {
+                let node = dot.nodes.iter_mut().find(|(node, _)| *node == chain[0]);
+                node.unwrap().1 = Some(label);
+            }
+        }
+        for pair in chain.windows(2) {
+            dot.edges.push((pair[0].clone(), pair[1].clone()));
+        }
+    }
+    if tokens.next().is_some() {
+        return Err(invalid("unexpected content after '}'"));
+    }
+    Ok(dot)
+}
+
+#[cfg(test)]
+mod test_dot {
+
+    use crate::GraphError;
+
+    use super::{parse, Dot};
+
+    /// Test for parsing the nodes, the labels and the edges of a digraph, skipping the
+    /// comments and the other attributes.
+    #[test]
+    fn parse_digraph() {
+        let src = r#"
+            // A pipeline.
+            strict digraph pipeline {
+                rankdir = LR;
+                node [shape=box];
+                fetch [label="Fetch \"data\"", color=red];
+                fetch -> parse -> store [weight=2]
+                /* The report only needs the parsed data. */
+                parse -> report;
+                # Declared late.
+                report [label=Report]
+            }
+        "#;
+        assert_eq!(
+            parse(src).unwrap(),
+            Dot {
+                nodes: vec![
+                    ("fetch".to_string(), Some("Fetch \"data\"".to_string())),
+                    ("parse".to_string(), None),
+                    ("store".to_string(), None),
+                    ("report".to_string(), Some("Report".to_string())),
+                ],
+                edges: vec![
+                    ("fetch".to_string(), "parse".to_string()),
+                    ("parse".to_string(), "store".to_string()),
+                    ("parse".to_string(), "report".to_string()),
+                ],
+            }
+        );
+    }
+
+    /// Test for rejecting the graphs that are not digraphs, or not supported.
+    #[test]
+    fn parse_invalid() {
+        for src in [
+            "graph { a -- b }",
+            "digraph { a -- b }",
+            "digraph { a -> }",
+            "digraph { subgraph x { a } }",
+            "digraph { a [label] }",
+            "digraph { a",
+            "digraph { \"a }",
+        ] {
+            assert!(
+                matches!(parse(src), Err(GraphError::InvalidDot(_))),
+                "{}",
+                src
+            );
+        }
+    }
+}
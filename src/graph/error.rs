@@ -0,0 +1,93 @@
+use std::fmt::Display;
+
+use crate::node::node::NodeId;
+
+/// # Graph Error Types
+/// Errors that may be raised while building or running a [`Graph`](super::graph::Graph).
+/// - GraphLoopDetected(names): the dependencies of the nodes form a loop, made of the nodes
+///   with the given names.
+/// - NodeTimeout(name): the node with the given name runs longer than its timeout.
+/// - BlockingInAsyncContext: a blocking start is called from inside a tokio runtime.
+/// - InvalidParallelism(n): the maximum parallelism `n` is not allowed.
+/// - UnknownNode(id): an edge leads to the node with the given id, which is not in the graph.
+/// - NodeNotFound(name): no node with the given name is in the graph.
+/// - InvalidOrder(reason): a given execution order does not match the nodes and edges.
+/// - OutputTooLarge(name, size): the output of the node with the given name has `size` bytes,
+///   more than the node allows.
+/// - InvalidRate(rate): the start rate `rate` is not allowed.
+/// - InvalidResource(name, resource): the node with the given name requires more of a resource
+///   than its pool holds, or a resource without a pool.
+/// - InvalidDot(reason): a graph in the DOT format cannot be imported.
+/// - DuplicateId(id, first, second): the nodes with the names `first` and `second` given to a
+///   graph have the same id.
+/// - MissingEnv(name, var): the node with the given name requires the environment variable
+///   `var`, which is not set.
+/// - DuplicateName(name): several nodes have the given name, while the graph resumes from a
+///   checkpoint matching the nodes by their names.
+#[derive(Debug)]
+pub enum GraphError {
+    GraphLoopDetected(Vec<String>),
+    NodeTimeout(String),
+    BlockingInAsyncContext,
+    InvalidParallelism(usize),
+    UnknownNode(NodeId),
+    NodeNotFound(String),
+    InvalidOrder(String),
+    OutputTooLarge(String, usize),
+    InvalidResource(String, String),
+    InvalidRate(u32),
+    InvalidDot(String),
+    DuplicateId(NodeId, String, String),
+    MissingEnv(String, String),
+    DuplicateName(String),
+}
+
+impl Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::GraphLoopDetected(names) => {
+                write!(f, "Graph contains a loop: [{}].", names.join(", "))
+            }
+            GraphError::NodeTimeout(name) => write!(f, "Node [{}] timed out.", name),
+            GraphError::BlockingInAsyncContext => write!(
+                f,
+                "Cannot block on a graph inside an asynchronous context, use `start_async` instead."
+            ),
+            GraphError::InvalidParallelism(n) => {
+                write!(f, "Invalid maximum parallelism {}, it must be positive.", n)
+            }
+            GraphError::UnknownNode(id) => write!(f, "Node [{}] is not in the graph.", id.0),
+            GraphError::NodeNotFound(name) => write!(f, "Node [{}] is not in the graph.", name),
+            GraphError::InvalidOrder(reason) => write!(f, "Invalid execution order: {}.", reason),
+            GraphError::OutputTooLarge(name, size) => write!(
+                f,
+                "Output of node [{}] has {} bytes, more than its maximum output size.",
+                name, size
+            ),
+            GraphError::InvalidResource(name, resource) => write!(
+                f,
+                "Node [{}] requires more of resource [{}] than its pool holds.",
+                name, resource
+            ),
+            GraphError::InvalidRate(rate) => {
+                write!(f, "Invalid start rate {}, it must be positive.", rate)
+            }
+            GraphError::InvalidDot(reason) => write!(f, "Invalid DOT graph: {}.", reason),
+            GraphError::DuplicateId(id, first, second) => write!(
+                f,
+                "Nodes [{}] and [{}] have the same id {}.",
+                first, second, id.0
+            ),
+            GraphError::MissingEnv(name, var) => write!(
+                f,
+                "Node [{}] requires environment variable [{}], which is not set.",
+                name, var
+            ),
+            GraphError::DuplicateName(name) => {
+                write!(f, "Several nodes have the same name [{}].", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
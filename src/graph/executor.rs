@@ -0,0 +1,28 @@
+use std::{future::Future, pin::Pin};
+
+/// The future running a node, spawned by a [`NodeExecutor`].
+pub type NodeFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// # Node executor
+///
+/// A [`NodeExecutor`] spawns the futures running the nodes of a
+/// [`Graph`](super::graph::Graph), e.g. to run them on a dedicated pool of threads instead of
+/// the runtime starting the graph. It is registered with `Graph::set_executor`, and
+/// [`TokioExecutor`] is used by default.
+///
+/// The spawned futures must be polled to completion. They use the tokio timers for the
+/// timeouts and retries of the nodes, so they must be polled inside a tokio runtime context.
+pub trait NodeExecutor: Send + Sync {
+    /// Spawn the future running a node.
+    fn spawn(&self, fut: NodeFuture);
+}
+
+/// The default [`NodeExecutor`], spawning the nodes as tasks of the current tokio runtime.
+#[derive(Default)]
+pub struct TokioExecutor;
+
+impl NodeExecutor for TokioExecutor {
+    fn spawn(&self, fut: NodeFuture) {
+        tokio::spawn(fut);
+    }
+}
@@ -1,22 +1,76 @@
 use std::hash::Hash;
-use std::sync::mpsc::channel;
+use std::ops::{Deref, DerefMut};
 use std::{
-    collections::{HashMap, HashSet},
-    panic::{self, AssertUnwindSafe},
-    sync::{atomic::AtomicBool, Arc},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    future::Future,
+    sync::{atomic::AtomicBool, Arc, OnceLock},
+    time::{Duration, Instant, SystemTime},
 };
+use std::{io, path::Path};
 
 use crate::{
-    connection::{in_channel::InChannel, information_packet::Content, out_channel::OutChannel},
-    node::node::{Node, NodeId, NodeTable},
-    utils::{env::EnvVar, execstate::ExecState},
+    connection::{
+        in_channel::InChannel, information_packet::Content, out_channel::OutChannel,
+        permit::RunPermit,
+    },
+    node::{
+        action::{Action, EmptyAction},
+        node::{Node, NodeId, NodeName, NodeTable},
+    },
+    utils::{
+        cache::Cache,
+        checkpoint,
+        env::EnvVar,
+        execstate::ExecState,
+        logger::{LogSink, NodeLogger},
+        panic::{panic_message, CatchPanic, QuietPanic},
+        progress::Progress,
+        rate::RateLimiter,
+        state::{NodeState, NodeStates},
+    },
     Output,
 };
 
-use log::{debug, error};
+use super::{
+    abort::{Abortable, GraphAbortHandle},
+    builder::GraphBuilder,
+    dot,
+    error::GraphError,
+    executor::{NodeExecutor, TokioExecutor},
+    observer::{GraphObserver, Observers},
+    pause::GraphPauseHandle,
+};
+
+use log::{debug, error, warn};
+use tokio::runtime::{Handle, Runtime};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
-use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tokio::sync::Semaphore;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// The default runtime shared by all the graphs started with [`Graph::start`].
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Get the default runtime, built on first use.
+fn default_runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| Runtime::new().unwrap())
+}
+
+/// Block on `fut` with the runtime of `handle`. Blocking is not allowed inside an
+/// asynchronous context, so this returns [`GraphError::BlockingInAsyncContext`] there.
+fn block_on<T>(
+    handle: &Handle,
+    fut: impl Future<Output = Result<T, GraphError>>,
+) -> Result<T, GraphError> {
+    if Handle::try_current().is_ok() {
+        error!("Cannot block on a graph inside an asynchronous context.");
+        return Err(GraphError::BlockingInAsyncContext);
+    }
+    handle.block_on(fut)
+}
 
 /// [`Graph`] is dagrs's main body.
 ///
@@ -34,7 +88,6 @@ use tokio::sync::Mutex;
 ///   This ensures that the `Graph` cannot be executed again without resetting its state.
 ///
 /// The [`Graph`] is designed to efficiently manage task execution with built-in fault tolerance and flexible scheduling.
-
 pub struct Graph {
     /// Define the Net struct that holds all nodes
     nodes: HashMap<NodeId, Box<dyn Node>>,
@@ -49,10 +102,62 @@ pub struct Graph {
     /// Mark whether the net task can continue to execute.
     /// When an error occurs during the execution of any task, This flag will still be set to true
     is_active: Arc<AtomicBool>,
+    /// Whether the graph has run since it was last reset, so that adding or removing a node
+    /// resets it first.
+    has_run: bool,
+    /// Whether the graph has been aborted, see [`Graph::abort_handle`].
+    aborted: Arc<watch::Sender<bool>>,
+    /// Whether the graph is paused, see [`Graph::pause_handle`].
+    paused: Arc<watch::Sender<bool>>,
     /// Node's in_degree, used for check loop
     in_degree: HashMap<NodeId, usize>,
+    /// The edges in the order they are added, used to rebuild the channels on reset.
+    edges: Vec<(NodeId, Vec<NodeId>)>,
+    /// The maximum number of nodes running at the same time, `None` means no limit.
+    max_parallelism: Option<usize>,
+    /// Whether the panics of the nodes skip the panic hook.
+    quiet_panics: bool,
+    /// The number of nodes resolved in the current run.
+    progress: Progress,
+    /// The states of the nodes in the current run.
+    states: NodeStates,
+    /// The cache of the outputs of the nodes with a cache key.
+    cache: Option<Arc<dyn Cache>>,
+    /// The outputs restored from a checkpoint, by node name, for the next run.
+    restored: HashMap<NodeName, Output>,
+    /// The outputs given by the user, by node name, for every run.
+    precomputed: HashMap<NodeName, Output>,
+    /// The execution order given by the user, used instead of the topological sort.
+    order: Option<Vec<NodeId>>,
+    /// The observers notified of the lifecycle events of the nodes.
+    observers: Vec<Arc<dyn GraphObserver>>,
+    /// Rewrites the execution order before each run.
+    rewriter: Option<SequenceRewriter>,
+    /// The conditions of the conditional edges, by successor, with their predecessors.
+    conditions: HashMap<NodeId, Vec<(NodeId, EdgeCondition)>>,
+    /// Spawns the futures running the nodes.
+    executor: Arc<dyn NodeExecutor>,
+    /// Receives the records logged by the nodes, see [`Graph::set_log_sink`].
+    log_sink: Option<Arc<dyn LogSink>>,
+    /// The capacity of each resource pool, see [`Graph::set_resource`].
+    resources: HashMap<String, u32>,
+    /// The longest a run may take, see [`Graph::set_deadline`].
+    deadline: Option<Duration>,
+    /// Whether the current run was aborted by its deadline.
+    deadline_exceeded: Arc<AtomicBool>,
+    /// The maximum number of node starts per period, see [`Graph::set_start_rate`].
+    start_rate: Option<(u32, Duration)>,
+    /// Whether SIGINT and SIGTERM abort the graph, see [`Graph::handle_signals`].
+    #[cfg(all(unix, feature = "signal"))]
+    handle_signals: bool,
 }
 
+/// See [`Graph::set_sequence_rewriter`].
+type SequenceRewriter = Box<dyn Fn(Vec<NodeId>, &Graph) -> Vec<NodeId> + Send + Sync>;
+
+/// See [`Graph::add_conditional_edge`].
+type EdgeCondition = Arc<dyn Fn(&Output) -> bool + Send + Sync>;
+
 impl Graph {
     /// Constructs a new `Graph`
     pub fn new() -> Self {
@@ -62,33 +167,196 @@ impl Graph {
             execute_states: HashMap::new(),
             env: Arc::new(EnvVar::new(NodeTable::default())),
             is_active: Arc::new(AtomicBool::new(true)),
+            has_run: false,
+            aborted: Arc::new(watch::Sender::new(false)),
+            paused: Arc::new(watch::Sender::new(false)),
             in_degree: HashMap::new(),
+            edges: Vec::new(),
+            max_parallelism: None,
+            quiet_panics: false,
+            progress: Progress::default(),
+            states: NodeStates::default(),
+            cache: None,
+            restored: HashMap::new(),
+            precomputed: HashMap::new(),
+            order: None,
+            observers: Vec::new(),
+            rewriter: None,
+            conditions: HashMap::new(),
+            executor: Arc::new(TokioExecutor),
+            log_sink: None,
+            resources: HashMap::new(),
+            deadline: None,
+            deadline_exceeded: Arc::new(AtomicBool::new(false)),
+            start_rate: None,
+            #[cfg(all(unix, feature = "signal"))]
+            handle_signals: false,
+        }
+    }
+
+    /// Constructs a new `Graph` holding the given nodes, which can be of different types.
+    ///
+    /// A node replaces the one before it with the same id, see [`Graph::try_with_nodes`] to
+    /// detect it.
+    pub fn with_nodes(nodes: Vec<Box<dyn Node>>) -> Self {
+        let mut graph = Self::new();
+        nodes.into_iter().for_each(|node| graph.add_node(node));
+        graph
+    }
+
+    /// Constructs a new `Graph` holding the given nodes, like [`Graph::with_nodes`]. Returns
+    /// [`GraphError::DuplicateId`] if several nodes have the same id, instead of keeping
+    /// only the last one.
+    pub fn try_with_nodes(nodes: Vec<Box<dyn Node>>) -> Result<Self, GraphError> {
+        let mut graph = Self::new();
+        for node in nodes {
+            graph.try_add_node(node)?;
         }
+        Ok(graph)
+    }
+
+    /// Get a [`GraphBuilder`] to build a graph by the ids of its nodes.
+    pub fn builder() -> GraphBuilder {
+        GraphBuilder::new()
     }
 
-    /// Reset the graph state but keep the nodes.
+    /// Reset the graph state but keep the nodes, the edges and the environment variables, so
+    /// that the graph can be started again. The outputs of the previous run are discarded.
+    ///
+    /// The channels closed by the previous run are rebuilt from the edges, and the variables
+    /// published by the nodes with [`EnvVar::set_shared`] are discarded: the graph runs on with
+    /// a clone of its environment, see [`EnvVar::clone`]. So an [`EnvVar`] given to the graph
+    /// before the reset keeps the variables published so far, but not the ones published by
+    /// the later runs.
     pub fn reset(&mut self) {
         self.execute_states = HashMap::new();
-        self.env = Arc::new(EnvVar::new(NodeTable::default()));
-        self.is_active = Arc::new(AtomicBool::new(true));
+        self.env = Arc::new(EnvVar::clone(&self.env));
+        self.is_active
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.has_run = false;
+        self.aborted.send_replace(false);
+        self.deadline_exceeded
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.progress.begin(0);
+        self.states.begin(&self.execute_states);
+        self.rewire();
+    }
+
+    /// Rebuild the channels of all the nodes from the edges.
+    fn rewire(&mut self) {
+        self.nodes.values_mut().for_each(|node| {
+            node.input_channels().channels.clear();
+            node.output_channels().channels.clear();
+        });
+        self.in_degree.values_mut().for_each(|degree| *degree = 0);
+        for (from_id, to_ids) in self.edges.clone() {
+            // The edges from a removed node are kept to be reported, see `Graph::remove_node`.
+            if self.nodes.contains_key(&from_id) {
+                self.connect(from_id, to_ids);
+            }
+        }
     }
 
-    /// Adds a new node to the `Graph`
+    /// Adds a new node to the `Graph`.
+    ///
+    /// A node added after the graph has run resets the graph, see [`Graph::reset`], so that
+    /// the graph with the new node can be started again.
+    ///
+    /// A node with the id of a node already in the graph replaces it, keeping its edges, and
+    /// a warning is logged. See [`Graph::try_add_node`] to reject it instead.
     pub fn add_node(&mut self, node: Box<dyn Node>) {
-        self.node_count = self.node_count + 1;
+        if self.has_run {
+            self.reset();
+        }
+        self.order = None;
         let id = node.id();
-        self.nodes.insert(id, node);
-        self.in_degree.insert(id, 0);
+        if let Some(replaced) = self.nodes.insert(id, node) {
+            warn!(
+                "Node [name: {}, id: {}] is replaced by a node with the same id.",
+                replaced.name(),
+                id.0
+            );
+            self.rewire();
+        } else {
+            self.node_count += 1;
+            self.in_degree.entry(id).or_insert(0);
+        }
+    }
+
+    /// Removes the node with the given id from the `Graph`, with the edges leading to it, and
+    /// returns it. `None` if no node has the id.
+    ///
+    /// The edges from the node are kept, so if other nodes depend on it, the graph fails to
+    /// validate with [`GraphError::UnknownNode`] until they are removed too. Like
+    /// [`Graph::add_node`], removing a node after the graph has run resets the graph.
+    pub fn remove_node(&mut self, id: &NodeId) -> Option<Box<dyn Node>> {
+        if self.has_run {
+            self.reset();
+        }
+        let node = self.nodes.remove(id)?;
+        self.node_count -= 1;
+        self.order = None;
+        self.in_degree.remove(id);
+        self.conditions.remove(id);
+        self.edges
+            .iter_mut()
+            .for_each(|(_, to_ids)| to_ids.retain(|to_id| to_id != id));
+        self.edges.retain(|(_, to_ids)| !to_ids.is_empty());
+        self.rewire();
+        Some(node)
+    }
+
+    /// Adds a new node to the `Graph` like [`Graph::add_node`]. Returns
+    /// [`GraphError::DuplicateId`] if a node with the same id is already in the graph.
+    pub fn try_add_node(&mut self, node: Box<dyn Node>) -> Result<(), GraphError> {
+        if let Some(existing) = self.nodes.get(&node.id()) {
+            return Err(GraphError::DuplicateId(
+                node.id(),
+                existing.name(),
+                node.name(),
+            ));
+        }
+        self.add_node(node);
+        Ok(())
     }
     /// Adds an edge between two nodes in the `Graph`.
     /// If the outgoing port of the sending node is empty and the number of receiving nodes is > 1, use the broadcast channel
     /// An MPSC channel is used if the outgoing port of the sending node is empty and the number of receiving nodes is equal to 1
     /// If the outgoing port of the sending node is not empty, adding any number of receiving nodes will change all relevant channels to broadcast
     pub fn add_edge(&mut self, from_id: NodeId, all_to_ids: Vec<NodeId>) {
+        self.order = None;
+        self.edges.push((from_id, all_to_ids.clone()));
+        self.connect(from_id, all_to_ids);
+    }
+
+    /// Adds an edge from `from_id` to `to_id`, such that the node `to_id` only runs if the
+    /// output of the node `from_id` is of type `T` and satisfies `condition`. Otherwise the
+    /// node `to_id` is skipped, see [`Node::should_run`], which does not fail the graph.
+    ///
+    /// The node `to_id` waits for the node `from_id` to finish before it runs.
+    pub fn add_conditional_edge<T: Send + Sync + 'static>(
+        &mut self,
+        from_id: NodeId,
+        to_id: NodeId,
+        condition: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) {
+        self.add_edge(from_id, vec![to_id]);
+        let condition: EdgeCondition = Arc::new(move |out: &Output| {
+            out.get_out()
+                .is_some_and(|content| content.get::<T>().is_some_and(&condition))
+        });
+        self.conditions
+            .entry(to_id)
+            .or_default()
+            .push((from_id, condition));
+    }
+
+    /// Build the channels of an edge, see [`Graph::add_edge`].
+    fn connect(&mut self, from_id: NodeId, all_to_ids: Vec<NodeId>) {
         let from_node = self.nodes.get_mut(&from_id).unwrap();
         let from_channel = from_node.output_channels();
         let to_ids = Self::remove_duplicates(all_to_ids);
-        if from_channel.0.is_empty() {
+        if from_channel.channels.is_empty() {
             if to_ids.len() > 1 {
                 let (bcst_sender, _) = broadcast::channel::<Content>(32);
                 {
@@ -108,7 +376,7 @@ impl Graph {
                         to_channel.insert(from_id, Arc::new(Mutex::new(InChannel::Bcst(receiver))));
                     }
                 }
-            } else if let Some(to_id) = to_ids.get(0) {
+            } else if let Some(to_id) = to_ids.first() {
                 let (tx, rx) = mpsc::channel::<Content>(32);
                 {
                     from_channel.insert(*to_id, Arc::new(OutChannel::Mpsc(tx.clone())));
@@ -122,32 +390,31 @@ impl Graph {
                     to_channel.insert(from_id, Arc::new(Mutex::new(InChannel::Mpsc(rx))));
                 }
             }
-        } else {
-            if to_ids.len() > 1
-                || (to_ids.len() == 1 && !from_channel.0.contains_key(to_ids.get(0).unwrap()))
+        } else if to_ids.len() > 1
+            || (to_ids.len() == 1 && !from_channel.channels.contains_key(to_ids.first().unwrap()))
+        {
+            let (bcst_sender, _) = broadcast::channel::<Content>(32);
             {
-                let (bcst_sender, _) = broadcast::channel::<Content>(32);
-                {
-                    for _channel in from_channel.0.values_mut() {
-                        *_channel = Arc::new(OutChannel::Bcst(bcst_sender.clone()));
-                    }
-                    for to_id in &to_ids {
-                        if !from_channel.0.contains_key(to_id) {
-                            self.in_degree
-                                .entry(*to_id)
-                                .and_modify(|e| *e += 1)
-                                .or_insert(0);
-                        }
-                        from_channel
-                            .insert(*to_id, Arc::new(OutChannel::Bcst(bcst_sender.clone())));
-                    }
+                for _channel in from_channel.channels.values_mut() {
+                    *_channel = Arc::new(OutChannel::Bcst(bcst_sender.clone()));
                 }
                 for to_id in &to_ids {
-                    if let Some(to_node) = self.nodes.get_mut(to_id) {
-                        let to_channel = to_node.input_channels();
-                        let receiver = bcst_sender.subscribe();
-                        to_channel.insert(from_id, Arc::new(Mutex::new(InChannel::Bcst(receiver))));
+                    if !from_channel.channels.contains_key(to_id) {
+                        self.in_degree
+                            .entry(*to_id)
+                            .and_modify(|e| *e += 1)
+                            .or_insert(0);
                     }
+                    from_channel.insert(*to_id, Arc::new(OutChannel::Bcst(bcst_sender.clone())));
+                }
+            }
+            // The earlier receivers also switch to the broadcast channel.
+            let all_to_ids: Vec<NodeId> = from_channel.channels.keys().copied().collect();
+            for to_id in &all_to_ids {
+                if let Some(to_node) = self.nodes.get_mut(to_id) {
+                    let to_channel = to_node.input_channels();
+                    let receiver = bcst_sender.subscribe();
+                    to_channel.insert(from_id, Arc::new(Mutex::new(InChannel::Bcst(receiver))));
                 }
             }
         }
@@ -160,185 +427,5382 @@ impl Graph {
             self.execute_states
                 .insert(node.id(), Arc::new(ExecState::new()));
         });
+        self.states.begin(&self.execute_states);
     }
-    /// This function is used for the execution of a single net.
+
+    /// Starts the graph and blocks until all nodes complete.
+    ///
+    /// This is a blocking convenience wrapper around [`Graph::start_async`]. The graph runs on
+    /// a default tokio runtime, which is built once and shared by all graphs.
+    ///
+    /// Returns `Ok(true)` if all nodes succeed, `Ok(false)` if any node fails or the graph
+    /// is not active.
+    pub fn start(&mut self) -> Result<bool, GraphError> {
+        self.start_on(default_runtime().handle())
+    }
+
+    /// Runs the graph and blocks until all nodes complete.
+    ///
+    /// This is kept for compatibility with the synchronous API, and errors are only logged.
+    #[deprecated(note = "use `Graph::start` or `Graph::start_async` instead")]
     pub fn run(&mut self) {
-        self.init();
-        let is_loop = self.check_loop();
-        if is_loop {
-            panic!("Graph contains a loop.");
+        if let Err(e) = self.start() {
+            error!("Graph execution failed: {}", e);
         }
+    }
+
+    /// Starts the graph on the runtime of the given handle and blocks until all nodes complete.
+    ///
+    /// Blocking is not allowed inside an asynchronous context, so calling this function from
+    /// inside a tokio runtime returns [`GraphError::BlockingInAsyncContext`]. Use
+    /// [`Graph::start_async`] there instead.
+    pub fn start_on(&mut self, handle: &Handle) -> Result<bool, GraphError> {
+        block_on(handle, self.start_async())
+    }
+
+    /// Starts the graph like [`Graph::start`], and returns the report of the run instead of
+    /// whether it succeeded. See [`Graph::execution_report`].
+    pub fn start_with_report(&mut self) -> Result<ExecutionReport, GraphError> {
+        self.start()?;
+        Ok(self.execution_report())
+    }
+
+    /// Starts the graph on the caller's tokio runtime and waits until all nodes complete.
+    pub async fn start_async(&mut self) -> Result<bool, GraphError> {
+        self.start_target(None).await
+    }
+
+    /// Runs only the node named `target` and the nodes it depends on, directly or not, and
+    /// blocks until they complete. The other nodes are not run at all. Returns
+    /// [`GraphError::NodeNotFound`] if no node is named `target`.
+    ///
+    /// The channels to the nodes not run are closed for the run, so the nodes run never wait
+    /// for them, and a lazy node whose successor is not run runs eagerly.
+    ///
+    /// Like [`Graph::start`], this runs on the default runtime and must not be called inside
+    /// an asynchronous context.
+    pub fn start_until(&mut self, target: &str) -> Result<bool, GraphError> {
+        block_on(default_runtime().handle(), self.start_target(Some(target)))
+    }
+
+    /// Starts the graph, or only the given target and its ancestors.
+    async fn start_target(&mut self, target: Option<&str>) -> Result<bool, GraphError> {
         if !self.is_active.load(std::sync::atomic::Ordering::Relaxed) {
-            eprintln!("Graph is not active. Aborting execution.");
-            return;
-        } else {
-            for (node_id, node) in &mut self.nodes {
-                let execute_state = self.execute_states[&node_id].clone();
-                panic::catch_unwind(AssertUnwindSafe(|| node.run(Arc::clone(&self.env))))
-                    .map_or_else(
-                        |_| {
-                            error!(
-                                "Execution failed [name: {}, id: {}]",
-                                node.name(),
-                                node_id.0,
-                            );
-                        },
-                        |out| {
-                            // Store execution results
-                            if out.is_err() {
-                                let error = out.get_err().unwrap_or("".to_string());
-                                error!(
-                                    "Execution failed [name: {}, id: {}] - {}",
-                                    node.name(),
-                                    node_id.0,
-                                    error
-                                );
-                                execute_state.set_output(out);
-                                execute_state.exe_fail();
+            error!("Graph is not active. Aborting execution.");
+            return Ok(false);
+        }
+        self.validate()?;
+        let ancestors = match target {
+            Some(name) => Some(self.with_ancestors(name)?),
+            None => None,
+        };
+        self.init();
+        match self.topo_sort() {
+            Some(mut sequence) => {
+                if let Some(rewriter) = &self.rewriter {
+                    sequence = rewriter(sequence, self);
+                    self.check_order(&sequence)?;
+                }
+                if let Some(ancestors) = ancestors {
+                    sequence.retain(|id| ancestors.contains(id));
+                    for id in &sequence {
+                        let node = self.nodes.get_mut(id).unwrap();
+                        node.output_channels()
+                            .channels
+                            .retain(|to_id, _| ancestors.contains(to_id));
+                    }
+                }
+                self.progress.begin(sequence.len());
+                self.log_sequence(&sequence);
+                Ok(self.run_sequence(sequence).await)
+            }
+            None => {
+                let e = self.loop_error();
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Get the node named `target` and all the nodes it depends on, directly or not.
+    fn with_ancestors(&self, target: &str) -> Result<HashSet<NodeId>, GraphError> {
+        let target = self.id_of(target)?;
+        let mut ancestors = reachable(target, &self.predecessors());
+        ancestors.insert(target);
+        Ok(ancestors)
+    }
+
+    /// Get the id of the node named `name`.
+    fn id_of(&self, name: &str) -> Result<NodeId, GraphError> {
+        self.nodes
+            .values()
+            .find(|node| node.name() == name)
+            .map(|node| node.id())
+            .ok_or_else(|| GraphError::NodeNotFound(name.to_string()))
+    }
+
+    /// This function is used for the execution of a single net.
+    ///
+    /// Every node is spawned by the executor in the given topological order, so that nodes
+    /// can communicate with each other through their channels while running. If the parallelism
+    /// is limited, a node only runs with a permit, which it gives back while it waits for its
+    /// channels.
+    async fn run_sequence(&mut self, sequence: Vec<NodeId>) -> bool {
+        let semaphore = self.max_parallelism.map(|n| Arc::new(Semaphore::new(n)));
+        let rate = self
+            .start_rate
+            .map(|(rate, per)| Arc::new(RateLimiter::new(rate, per)));
+        // The timers of the run are spawned by the executor like the nodes, and stopped once
+        // the run ends.
+        let (stop, stopped) = watch::channel(false);
+        let stop = StopGuard(stop);
+        if let Some(deadline) = self.deadline {
+            let abort = self.abort_handle();
+            let exceeded = self.deadline_exceeded.clone();
+            let timer = Abortable::new(
+                async move {
+                    tokio::time::sleep(deadline).await;
+                    error!("Graph execution exceeded its deadline of {:?}.", deadline);
+                    exceeded.store(true, std::sync::atomic::Ordering::Relaxed);
+                    abort.abort();
+                },
+                stopped.clone(),
+            );
+            self.executor.spawn(Box::pin(async move {
+                timer.await;
+            }));
+        }
+        #[cfg(all(unix, feature = "signal"))]
+        let signals = match self.handle_signals {
+            true => self.listen_signals(&stopped),
+            false => None,
+        };
+        let pools: HashMap<String, Arc<Semaphore>> = self
+            .resources
+            .iter()
+            .map(|(name, &n)| (name.clone(), Arc::new(Semaphore::new(n as usize))))
+            .collect();
+        let predecessors = self.predecessors();
+        let mut handles = Vec::with_capacity(self.nodes.len());
+        let observer = match self.observers[..] {
+            [] => None,
+            _ => Some(Arc::new(Observers(self.observers.clone())) as Arc<dyn GraphObserver>),
+        };
+        for node_id in sequence {
+            let mut node = self.nodes.remove(&node_id).unwrap();
+            let max_output_size = node.max_output_size();
+            node.output_channels().set_max_size(max_output_size);
+            let permit = semaphore.clone().map(RunPermit::new);
+            node.input_channels().set_permit(permit.clone());
+            node.output_channels().set_permit(permit.clone());
+            let lazy = node.is_lazy();
+            let reads_inputs = node.reads_inputs();
+            let successor = match node.output_channels().channels.keys().collect::<Vec<_>>()[..] {
+                [id] if lazy => self.execute_states.get(id).cloned(),
+                _ => None,
+            };
+            let name = node.name();
+            // The resources are acquired by name, so that no two nodes wait on each other.
+            let mut required = node.resources();
+            required.sort();
+            let resources = required
+                .into_iter()
+                .map(|(name, amount)| (pools[&name].clone(), amount))
+                .collect();
+            let node = Arc::new(Mutex::new(Some(node)));
+            let node_ref = Arc::clone(&node);
+            let env = Arc::clone(&self.env);
+            let execute_state = self.execute_states[&node_id].clone();
+            let resolved = self.progress.resolve_on_drop();
+            let cache = self.cache.clone();
+            let stored = self
+                .restored
+                .remove(&name)
+                .or_else(|| self.precomputed.get(&name).cloned());
+            let node_observer = observer.clone();
+            let inputs = match reads_inputs {
+                true => predecessors
+                    .get(&node_id)
+                    .into_iter()
+                    .flatten()
+                    .map(|id| (*id, self.execute_states[id].clone()))
+                    .collect(),
+                false => Vec::new(),
+            };
+            let conditions = self
+                .conditions
+                .get(&node_id)
+                .into_iter()
+                .flatten()
+                .map(|(id, condition)| (self.execute_states[id].clone(), condition.clone()))
+                .collect();
+            let paused = self.paused.subscribe();
+            let rate = rate.clone();
+            let logger = NodeLogger::new(node_id, name.clone(), self.log_sink.clone());
+            if let Some(observer) = &observer {
+                observer.on_node_scheduled(node_id, &name);
+            }
+            let fut = async move {
+                let _resolved = resolved;
+                Self::execute_node(
+                    node_ref,
+                    execute_state,
+                    successor,
+                    inputs,
+                    conditions,
+                    paused,
+                    resources,
+                    rate,
+                    permit,
+                    env,
+                    cache,
+                    stored,
+                    node_observer,
+                )
+                .await
+            };
+            // The node is cancelled once the graph is aborted, and its result is sent back
+            // whichever executor runs it.
+            let fut = Abortable::new(CatchPanic::new(logger.scope(fut)), self.aborted.subscribe());
+            let (sender, receiver) = oneshot::channel();
+            let execute_state = self.execute_states[&node_id].clone();
+            let node_observer = observer.clone();
+            let task_name = name.clone();
+            let aborted = self.aborted.subscribe();
+            let task = async move {
+                let finished = FinishGuard(execute_state.clone());
+                let result = match fut.await {
+                    // The graph may be aborted while the node is polled, e.g. when it fails
+                    // because a predecessor was cancelled and closed its channels. A node that
+                    // succeeded keeps its output.
+                    Some(Ok(out)) if out.is_err() && *aborted.borrow() => {
+                        execute_state.mark_cancelled();
+                        Err("aborted".to_string())
+                    }
+                    Some(Ok(out)) => {
+                        if !execute_state.skipped() {
+                            execute_state.set_state(if out.is_err() {
+                                NodeState::Failed
                             } else {
-                                execute_state.set_output(out);
-                                execute_state.exe_success();
-                                debug!(
-                                    "Execution succeed [name: {}, id: {}]",
-                                    node.name(),
-                                    node_id.0
-                                );
-                            }
+                                NodeState::Succeeded
+                            });
+                        }
+                        Ok(out)
+                    }
+                    Some(Err(payload)) => {
+                        execute_state.set_state(NodeState::Failed);
+                        Err(format!("panicked: {}", panic_message(&*payload)))
+                    }
+                    None => {
+                        execute_state.mark_cancelled();
+                        Err("aborted".to_string())
+                    }
+                };
+                // The final state of the node is reported once, before its successors see
+                // it finished.
+                if let Some(observer) = node_observer {
+                    let duration = execute_state.duration().unwrap_or_default();
+                    match &result {
+                        Ok(out) => match out.get_err() {
+                            Some(e) => observer.on_node_failed(node_id, &task_name, &e, duration),
+                            None => observer.on_node_succeeded(node_id, &task_name, duration),
                         },
-                    )
+                        Err(e) => observer.on_node_failed(node_id, &task_name, e, duration),
+                    }
+                }
+                if let Ok(out) = &result {
+                    finished.0.finish(out.clone());
+                }
+                drop(finished);
+                let _ = sender.send(result);
+            };
+            if self.quiet_panics {
+                self.executor.spawn(Box::pin(QuietPanic::new(task)));
+            } else {
+                self.executor.spawn(Box::pin(task));
+            }
+            handles.push((node_id, name, node, receiver));
+        }
+
+        let mut success = true;
+        for (node_id, name, node, receiver) in handles {
+            let execute_state = &self.execute_states[&node_id];
+            let result = match receiver.await {
+                Ok(result) => result,
+                Err(_) => {
+                    let error = "dropped by the executor".to_string();
+                    execute_state.set_state(NodeState::Failed);
+                    if let Some(observer) = &observer {
+                        let duration = execute_state.duration().unwrap_or_default();
+                        observer.on_node_failed(node_id, &name, &error, duration);
+                    }
+                    Err(error)
+                }
+            };
+            match result {
+                Err(error) => {
+                    error!(
+                        "Execution failed [name: {}, id: {}] - {}",
+                        name, node_id.0, error
+                    );
+                    execute_state.set_output(Output::error(error));
+                    execute_state.exe_fail();
+                    success = false;
+                }
+                Ok(out) => {
+                    // Store execution results
+                    if out.is_err() {
+                        let error = out.get_err().unwrap_or("".to_string());
+                        error!(
+                            "Execution failed [name: {}, id: {}] - {}",
+                            name, node_id.0, error
+                        );
+                        execute_state.set_output(out);
+                        execute_state.exe_fail();
+                        success = false;
+                    } else {
+                        execute_state.set_output(out);
+                        execute_state.exe_success();
+                        debug!("Execution succeed [name: {}, id: {}]", name, node_id.0);
+                    }
+                }
+            }
+            // The node is taken back from its lock, so that it does not matter whether the
+            // executor still references the finished task.
+            match node.lock().await.take() {
+                Some(node) => {
+                    self.nodes.insert(node_id, node);
+                }
+                None => error!("Node is missing after its execution [name: {}]", name),
             }
         }
+        drop(stop);
+        #[cfg(all(unix, feature = "signal"))]
+        drop(signals);
+        if *self.aborted.borrow() {
+            error!("Graph execution aborted.");
+            success = false;
+        }
         self.is_active
             .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.has_run = true;
+        if let Some(observer) = &observer {
+            observer.on_graph_completed(success);
+        }
+        success
     }
 
-    ///See if the graph has loop
-    pub fn check_loop(&mut self) -> bool {
-        let mut queue: Vec<NodeId> = self
-            .in_degree
-            .iter()
-            .filter_map(|(&node_id, &degree)| if degree == 0 { Some(node_id) } else { None })
-            .collect();
-
-        let mut in_degree = self.in_degree.clone();
-        let mut processed_count = 0;
+    /// Abort the graph once SIGINT or SIGTERM is received, until the returned guard is dropped.
+    /// The signals are received by the default runtime, so that the runtime running the graph
+    /// needs no IO driver.
+    #[cfg(all(unix, feature = "signal"))]
+    fn listen_signals(
+        &self,
+        stopped: &watch::Receiver<bool>,
+    ) -> Option<crate::utils::signal::SignalGuard> {
+        let runtime = default_runtime();
+        let (mut listener, guard) = match crate::utils::signal::listen(runtime.handle()) {
+            Ok(listening) => listening,
+            Err(e) => {
+                warn!(
+                    "Failed to listen to signals, they do not abort the graph: {}",
+                    e
+                );
+                return None;
+            }
+        };
+        let abort = self.abort_handle();
+        let timer = Abortable::new(
+            async move {
+                listener.recv().await;
+                error!("Graph execution interrupted by a signal.");
+                abort.abort();
+            },
+            stopped.clone(),
+        );
+        self.executor.spawn(Box::pin(async move {
+            timer.await;
+        }));
+        Some(guard)
+    }
 
-        while let Some(node_id) = queue.pop() {
-            processed_count += 1;
-            let node = self.nodes.get_mut(&node_id).unwrap();
-            let out = node.output_channels();
-            for (id, channel) in out.0.iter() {
-                if let Some(degree) = in_degree.get_mut(id) {
-                    *degree -= 1;
-                    if *degree == 0 {
-                        queue.push(id.clone());
+    /// Execute a single node, bounded by the node's timeout if it declares one. A failed run
+    /// is retried as long as the node has attempts left.
+    ///
+    /// If `successor` is given, the node is lazy and waits for that successor to start first.
+    /// The node waits for the predecessors of its `conditions` to finish, and is skipped with
+    /// an empty output unless their outputs satisfy the conditions. No attempt to run the
+    /// node begins while the graph is `paused`, and each attempt holds the given amounts of
+    /// `resources`, then waits for the start `rate` if any.
+    /// If the output of the node is `stored` or found in `cache`, the node is skipped and
+    /// that output is sent to its successors. If the node should not run, it is skipped with
+    /// an empty output. Once the node completes or panics, its output channels are closed, so that its successors
+    /// receive [`RecvErr::Closed`](crate::RecvErr) instead of waiting forever.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_node(
+        node: Arc<Mutex<Option<Box<dyn Node>>>>,
+        execute_state: Arc<ExecState>,
+        successor: Option<Arc<ExecState>>,
+        inputs: Vec<(NodeId, Arc<ExecState>)>,
+        conditions: Vec<(Arc<ExecState>, EdgeCondition)>,
+        mut paused: watch::Receiver<bool>,
+        resources: Vec<(Arc<Semaphore>, u32)>,
+        rate: Option<Arc<RateLimiter>>,
+        permit: Option<RunPermit>,
+        env: Arc<EnvVar>,
+        cache: Option<Arc<dyn Cache>>,
+        stored: Option<Output>,
+        observer: Option<Arc<dyn GraphObserver>>,
+    ) -> Output {
+        execute_state.set_state(NodeState::WaitingOnDeps);
+        if let Some(successor) = successor {
+            successor.wait_started().await;
+        }
+        let mut node = ClosingGuard(node.lock_owned().await);
+        execute_state.mark_started();
+        if let Some(observer) = &observer {
+            observer.on_node_started(node.id(), &node.name());
+        }
+        // The outputs of the predecessors are only gathered for a node reading them, once
+        // they have all finished.
+        let mut outputs = HashMap::new();
+        if stored.is_none() {
+            for (id, predecessor) in inputs {
+                predecessor.wait_finished().await;
+                outputs.insert(id, predecessor.get_full_output());
+            }
+        }
+        let cache = cache.and_then(|cache| Some((cache, node.cache_key(&env, &outputs)?)));
+        let skipped = stored.or_else(|| {
+            let (cache, key) = cache.as_ref()?;
+            Some(Output::from_content(cache.get(key)?))
+        });
+        if let Some(out) = skipped {
+            debug!(
+                "Execution skipped with stored output [name: {}, id: {}]",
+                node.name(),
+                node.id().0
+            );
+            execute_state.mark_skipped();
+            if let Some(observer) = &observer {
+                observer.on_node_skipped(node.id(), &node.name());
+            }
+            if let Some(content) = out.get_out() {
+                if let Err(e) = node.output_channels().send_to_all(content).await {
+                    warn!(
+                        "Failed to send stored output [name: {}]: {:?}",
+                        node.name(),
+                        e
+                    );
+                }
+            }
+            return out;
+        }
+        let mut should_run = node.should_run(&env, &outputs);
+        for (predecessor, condition) in conditions {
+            predecessor.wait_finished().await;
+            should_run = should_run && condition(&predecessor.get_full_output());
+        }
+        if !should_run {
+            debug!(
+                "Execution skipped by condition [name: {}, id: {}]",
+                node.name(),
+                node.id().0
+            );
+            execute_state.mark_skipped();
+            if let Some(observer) = &observer {
+                observer.on_node_skipped(node.id(), &node.name());
+            }
+            return Output::empty();
+        }
+        let policy = node.retry_policy();
+        let max_attempts = policy.max_attempts();
+        let start = Instant::now();
+        // Only the time spent running the attempts is measured, not the waits before them.
+        let mut started = None;
+        let mut running = Duration::ZERO;
+        // The inputs of a node that may be retried are received again by each attempt, and
+        // its successors only receive the contents sent by the attempt that succeeds.
+        if max_attempts > 1 {
+            node.input_channels().set_replay(true);
+            node.output_channels().set_held(true);
+        }
+        let mut attempt = 1;
+        let out = loop {
+            let _ = paused.wait_for(|paused| !*paused).await;
+            let mut permits = Vec::with_capacity(resources.len());
+            for (pool, amount) in &resources {
+                permits.push(pool.acquire_many(*amount).await.unwrap());
+            }
+            if let Some(rate) = &rate {
+                rate.acquire().await;
+            }
+            if let Some(permit) = &permit {
+                permit.acquire().await;
+            }
+            execute_state.set_state(NodeState::Running);
+            execute_state.set_attempts(attempt);
+            let run_start = Instant::now();
+            started.get_or_insert(run_start);
+            let mut timed_out = false;
+            let out = match node.timeout() {
+                Some(timeout) => match tokio::time::timeout(timeout, node.run(env.clone())).await {
+                    Ok(out) => out,
+                    Err(_) => {
+                        timed_out = true;
+                        error!(
+                            "Execution timed out after {:?} [name: {}, id: {}]",
+                            timeout,
+                            node.name(),
+                            node.id().0
+                        );
+                        Output::error(GraphError::NodeTimeout(node.name()).to_string())
                     }
+                },
+                None => node.run(env.clone()).await,
+            };
+            running += run_start.elapsed();
+            execute_state.set_timed_out(timed_out);
+            if let Some(permit) = &permit {
+                permit.release();
+            }
+            drop(permits);
+            if !out.is_err() || attempt >= max_attempts {
+                break out;
+            }
+            let delay = policy.delay(attempt);
+            if !policy.allows_retry_after(start.elapsed() + delay) {
+                warn!(
+                    "Execution failed at attempt {}/{} [name: {}, id: {}], retry duration exhausted",
+                    attempt,
+                    max_attempts,
+                    node.name(),
+                    node.id().0
+                );
+                break out;
+            }
+            warn!(
+                "Execution failed at attempt {}/{} [name: {}, id: {}], retry in {:?}",
+                attempt,
+                max_attempts,
+                node.name(),
+                node.id().0,
+                delay
+            );
+            node.input_channels().rewind();
+            node.output_channels().discard_held();
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        };
+        if max_attempts > 1 {
+            node.input_channels().set_replay(false);
+            if !out.is_err() {
+                if let Err(e) = node.output_channels().send_held().await {
+                    warn!(
+                        "Failed to send the contents of the last attempt [name: {}]: {:?}",
+                        node.name(),
+                        e
+                    );
                 }
             }
+            node.output_channels().set_held(false);
+        }
+        execute_state.set_timing(started.unwrap_or(start), running);
+        let out = node.post_process(out);
+        let size = out.get_out().and_then(|content| content.size());
+        match (size, node.max_output_size()) {
+            (Some(size), Some(max)) if size > max => {
+                error!(
+                    "Output of {} bytes exceeds the maximum of {} bytes [name: {}, id: {}]",
+                    size,
+                    max,
+                    node.name(),
+                    node.id().0
+                );
+                return Output::error(GraphError::OutputTooLarge(node.name(), size).to_string());
+            }
+            _ => {}
         }
-        processed_count < self.node_count
+        if let (Some((cache, key)), Some(content)) = (cache, out.get_out()) {
+            cache.put(&key, content);
+        }
+        out
     }
 
-    /// Get the output of all tasks.
-    pub fn get_results<T: Send + Sync + 'static>(&self) -> HashMap<NodeId, Option<Arc<T>>> {
-        self.execute_states
+    /// See if the graph has loop
+    pub fn check_loop(&mut self) -> bool {
+        self.topo_sort().is_none()
+    }
+
+    /// Get the names of the nodes in the order they are going to be started, without running
+    /// the graph. See [`Graph::dry_run`] for the errors.
+    pub fn execution_order(&self) -> Result<Vec<NodeName>, GraphError> {
+        self.validate()?;
+        let sequence = self.topo_sort().ok_or_else(|| self.loop_error())?;
+        Ok(sequence
             .iter()
-            .map(|(&id, state)| {
-                let output = match state.get_output() {
-                    Some(content) => content.into_inner(),
-                    None => None,
+            .map(|node_id| self.nodes[node_id].name())
+            .collect())
+    }
+
+    /// Export the graph in the Graphviz DOT format. Each node is identified by its id and
+    /// labeled with its name, and each edge points from a node to its successor.
+    ///
+    /// Once the graph is initialized (see [`Graph::init`]), each node is also annotated with
+    /// its position in the execution order, starting from 0.
+    pub fn to_dot(&self) -> String {
+        let order: HashMap<NodeId, usize> = match self.topo_sort() {
+            Some(sequence) if !self.execute_states.is_empty() => sequence
+                .into_iter()
+                .enumerate()
+                .map(|(position, id)| (id, position))
+                .collect(),
+            _ => HashMap::new(),
+        };
+        let mut ids: Vec<&NodeId> = self.nodes.keys().collect();
+        ids.sort_by_key(|id| id.0);
+        let mut dot = String::from("digraph {\n");
+        for id in ids {
+            let name = self.nodes[id].name();
+            let label = name.replace('\\', "\\\\").replace('"', "\\\"");
+            match order.get(id) {
+                Some(position) => dot.push_str(&format!(
+                    "    {} [label=\"{}\", xlabel=\"{}\"];\n",
+                    id.0, label, position
+                )),
+                None => dot.push_str(&format!("    {} [label=\"{}\"];\n", id.0, label)),
+            }
+        }
+        let successors = self.successors();
+        let mut from_ids: Vec<&NodeId> = successors.keys().collect();
+        from_ids.sort_by_key(|id| id.0);
+        for from_id in from_ids {
+            let mut to_ids: Vec<&NodeId> = successors[from_id].iter().collect();
+            to_ids.sort_by_key(|id| id.0);
+            for to_id in to_ids {
+                dot.push_str(&format!("    {} -> {};\n", from_id.0, to_id.0));
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Import a digraph in the Graphviz DOT format, e.g. exported by [`Graph::to_dot`]. Each
+    /// node becomes a [`DefaultNode`] named after its label, or its id if it has no label,
+    /// and each edge an edge of the graph. The other attributes are ignored.
+    ///
+    /// The action of a node is taken from `actions` by its name. The nodes without an action,
+    /// and the nodes after the first one with the same name, get an [`EmptyAction`].
+    /// Returns [`GraphError::InvalidDot`] if `src` is not a supported digraph.
+    ///
+    /// [`DefaultNode`]: crate::DefaultNode
+    pub fn from_dot(
+        src: &str,
+        mut actions: HashMap<String, Box<dyn Action>>,
+    ) -> Result<Graph, GraphError> {
+        let dot = dot::parse(src)?;
+        let mut builder = GraphBuilder::new();
+        let ids: HashMap<String, NodeId> = dot
+            .nodes
+            .into_iter()
+            .map(|(id, label)| {
+                let name = label.unwrap_or_else(|| id.clone());
+                let action = actions
+                    .remove(&name)
+                    .unwrap_or_else(|| Box::new(EmptyAction));
+                (id, builder.add_node(name, action))
+            })
+            .collect();
+        for (from, to) in dot.edges {
+            builder.add_dependency(ids[&from], ids[&to]);
+        }
+        builder.build()
+    }
+
+    /// Export the timing of the last run in the Chrome trace event format, to be loaded in
+    /// `about:tracing` or Perfetto. Each node that ran is a complete event (`"ph": "X"`),
+    /// with its start and duration in microseconds since the first node started.
+    ///
+    /// The nodes that ran at the same time are put on different threads (`tid`), so that the
+    /// parallelism of the run shows as parallel tracks.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut timings: Vec<(NodeId, Instant, Duration)> = self
+            .execute_states
+            .iter()
+            .filter_map(|(&id, state)| {
+                let (start, duration) = state.timing()?;
+                Some((id, start, duration))
+            })
+            .collect();
+        timings.sort_by_key(|&(id, start, _)| (start, id.0));
+        let origin = timings.first().map(|&(_, start, _)| start);
+        // The end of the last event on each track.
+        let mut tracks: Vec<Instant> = Vec::new();
+        let events: Vec<serde_json::Value> = timings
+            .into_iter()
+            .map(|(id, start, duration)| {
+                let end = start + duration;
+                let tid = match tracks.iter().position(|&last| last <= start) {
+                    Some(tid) => {
+                        tracks[tid] = end;
+                        tid
+                    }
+                    None => {
+                        tracks.push(end);
+                        tracks.len() - 1
+                    }
                 };
-                (id, output)
+                let name = self.nodes.get(&id).map(|node| node.name());
+                serde_json::json!({
+                    "name": name.unwrap_or_else(|| id.0.to_string()),
+                    "ph": "X",
+                    "ts": origin.map_or(0, |origin| (start - origin).as_micros()) as u64,
+                    "dur": duration.as_micros() as u64,
+                    "pid": 1,
+                    "tid": tid,
+                    "args": { "id": id.0 },
+                })
             })
-            .collect()
+            .collect();
+        serde_json::json!({ "traceEvents": events, "displayTimeUnit": "ms" }).to_string()
     }
-    pub fn get_outputs(&self) -> HashMap<NodeId, Output> {
-        self.execute_states
+
+    /// Validate the graph and get the plan of the nodes in the order they are going to be
+    /// started, without running any node. Returns [`GraphError::UnknownNode`] if an edge
+    /// leads to a node not in the graph, [`GraphError::MissingEnv`] if a node requires an
+    /// environment variable not set (see [`Node::required_env`]), or
+    /// [`GraphError::GraphLoopDetected`] if the graph has loop.
+    pub fn dry_run(&self) -> Result<Vec<PlannedNode>, GraphError> {
+        self.validate()?;
+        let sequence = self.topo_sort().ok_or_else(|| self.loop_error())?;
+        self.log_sequence(&sequence);
+        let mut predecessors = self.predecessors();
+        let positions: HashMap<NodeId, usize> = sequence
             .iter()
-            .map(|(&id, state)| {
-                let t = state.get_full_output();
-                (id, t)
+            .enumerate()
+            .map(|(position, id)| (*id, position))
+            .collect();
+        Ok(sequence
+            .iter()
+            .map(|id| {
+                let mut ids = predecessors.remove(id).unwrap_or_default();
+                ids.sort_by_key(|id| positions[id]);
+                PlannedNode {
+                    id: *id,
+                    name: self.nodes[id].name(),
+                    predecessors: ids.iter().map(|id| self.nodes[id].name()).collect(),
+                }
             })
-            .collect()
+            .collect())
     }
 
-    /// Before the dag starts executing, set the dag's global environment variable.
-    pub fn set_env(&mut self, env: EnvVar) {
-        self.env = Arc::new(env);
+    /// Log the execution order of the nodes.
+    fn log_sequence(&self, sequence: &[NodeId]) {
+        let names: String = sequence
+            .iter()
+            .map(|id| format!(" -> {}", self.nodes[id].name()))
+            .collect();
+        debug!("[Start]{} -> [End]", names);
     }
 
-    ///Remove duplicate elements
-    fn remove_duplicates<T>(vec: Vec<T>) -> Vec<T>
-    where
-        T: Eq + Hash + Clone,
-    {
-        let mut seen = HashSet::new();
-        vec.into_iter().filter(|x| seen.insert(x.clone())).collect()
+    /// Run the nodes in the given order instead of sorting them, e.g. when the order is
+    /// already computed elsewhere. The order must contain every node exactly once, and
+    /// put each node after all its predecessors. Adding nodes or edges discards the order.
+    pub fn set_execution_order(&mut self, order: Vec<NodeId>) -> Result<(), GraphError> {
+        self.validate()?;
+        self.check_order(&order)?;
+        self.order = Some(order);
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::node::default_node::DefaultNode;
-    use crate::{
-        Action, Content, EnvVar, InChannels, Node, NodeName, NodeTable, OutChannels, Output,
-    };
-    use async_trait::async_trait;
-    use std::sync::Arc;
+    /// Rewrite the execution order before each run with `rewriter`, which gets the order
+    /// computed as the graph would run it. The rewritten order must still contain every node
+    /// exactly once and put each node after all its predecessors, otherwise the run fails
+    /// with [`GraphError::InvalidOrder`].
+    pub fn set_sequence_rewriter(
+        &mut self,
+        rewriter: impl Fn(Vec<NodeId>, &Graph) -> Vec<NodeId> + Send + Sync + 'static,
+    ) {
+        self.rewriter = Some(Box::new(rewriter));
+    }
 
-    /// An implementation of [`Action`] that returns [`Output::Out`] containing a String "Hello world" from default_node.rs.
-    #[derive(Default)]
-    pub struct HelloAction;
-    #[async_trait]
-    impl Action for HelloAction {
-        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
-            Output::Out(Some(Content::new("Hello world".to_string())))
+    /// Check that the order contains every node exactly once, after all its predecessors.
+    fn check_order(&self, order: &[NodeId]) -> Result<(), GraphError> {
+        let mut positions = HashMap::with_capacity(order.len());
+        for (position, id) in order.iter().enumerate() {
+            if !self.nodes.contains_key(id) {
+                return Err(GraphError::UnknownNode(*id));
+            }
+            if positions.insert(*id, position).is_some() {
+                return Err(GraphError::InvalidOrder(format!(
+                    "node [{}] appears twice",
+                    self.nodes[id].name()
+                )));
+            }
+        }
+        if let Some(id) = self.nodes.keys().find(|id| !positions.contains_key(id)) {
+            return Err(GraphError::InvalidOrder(format!(
+                "node [{}] is missing",
+                self.nodes[id].name()
+            )));
+        }
+        for (from_id, to_ids) in self.successors() {
+            if let Some(to_id) = to_ids
+                .iter()
+                .find(|id| positions[id] <= positions[&from_id])
+            {
+                return Err(GraphError::InvalidOrder(format!(
+                    "node [{}] comes before its predecessor [{}]",
+                    self.nodes[to_id].name(),
+                    self.nodes[&from_id].name()
+                )));
+            }
         }
+        Ok(())
     }
 
-    impl HelloAction {
-        pub fn new() -> Box<Self> {
-            Box::new(Self::default())
+    /// Check that all the edges lead to nodes in the graph, and that the pools of the
+    /// resources hold enough for the nodes requiring them.
+    fn validate(&self) -> Result<(), GraphError> {
+        if let Some(id) = self
+            .in_degree
+            .keys()
+            .chain(self.edges.iter().map(|(from_id, _)| from_id))
+            .find(|id| !self.nodes.contains_key(id))
+        {
+            return Err(GraphError::UnknownNode(*id));
+        }
+        for node in self.nodes.values() {
+            for (resource, amount) in node.resources() {
+                if self
+                    .resources
+                    .get(&resource)
+                    .is_none_or(|&pool| amount > pool)
+                {
+                    return Err(GraphError::InvalidResource(node.name(), resource));
+                }
+            }
+            if let Some(var) = node
+                .required_env()
+                .into_iter()
+                .find(|var| !self.env.contains(var))
+            {
+                return Err(GraphError::MissingEnv(node.name(), var));
+            }
+        }
+        if !self.restored.is_empty() {
+            if let Some(name) = self.duplicate_name() {
+                return Err(GraphError::DuplicateName(name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get a name shared by several nodes, if any.
+    fn duplicate_name(&self) -> Option<NodeName> {
+        let mut names = HashSet::new();
+        self.nodes
+            .values()
+            .map(|node| node.name())
+            .find(|name| !names.insert(name.clone()))
+    }
+
+    /// Sort the nodes topologically, returns `None` if the graph has loop. The order given by
+    /// [`Graph::set_execution_order`] is used instead if any.
+    ///
+    /// Among the nodes ready at the same time, the one with the highest priority comes first,
+    /// then the one with the smallest id, so the same graph is always sorted in the same
+    /// order. See [`Node::priority`].
+    fn topo_sort(&self) -> Option<Vec<NodeId>> {
+        if let Some(order) = &self.order {
+            return Some(order.clone());
+        }
+        let ready = |id: NodeId| {
+            let priority = self.nodes.get(&id).map_or(0, |node| node.priority());
+            (priority, Reverse(id))
+        };
+        let mut queue: BinaryHeap<(i32, Reverse<NodeId>)> = self
+            .in_degree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&node_id, _)| ready(node_id))
+            .collect();
+
+        let successors = self.successors();
+        let mut in_degree = self.in_degree.clone();
+        let mut sequence = Vec::with_capacity(self.node_count);
+
+        while let Some((_, Reverse(node_id))) = queue.pop() {
+            sequence.push(node_id);
+            for id in successors.get(&node_id).into_iter().flatten() {
+                if let Some(degree) = in_degree.get_mut(id) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        queue.push(ready(*id));
+                    }
+                }
+            }
+        }
+        if sequence.len() < self.node_count {
+            None
+        } else {
+            Some(sequence)
+        }
+    }
+
+    /// Find a loop in the graph by depth-first search, returns the nodes forming the loop
+    /// in order, or `None` if the graph has no loop.
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        /// Visit the successors of `id`, `path` holds the nodes being visited.
+        fn visit(
+            id: NodeId,
+            successors: &HashMap<NodeId, HashSet<NodeId>>,
+            visited: &mut HashSet<NodeId>,
+            path: &mut Vec<NodeId>,
+        ) -> Option<Vec<NodeId>> {
+            if let Some(position) = path.iter().position(|node| *node == id) {
+                return Some(path[position..].to_vec());
+            }
+            if !visited.insert(id) {
+                return None;
+            }
+            path.push(id);
+            for next in successors.get(&id).into_iter().flatten() {
+                if let Some(cycle) = visit(*next, successors, visited, path) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            None
+        }
+
+        let successors = self.successors();
+        let mut ids: Vec<&NodeId> = self.nodes.keys().collect();
+        ids.sort_by_key(|id| id.0);
+        let mut visited = HashSet::new();
+        ids.into_iter()
+            .find_map(|id| visit(*id, &successors, &mut visited, &mut Vec::new()))
+    }
+
+    /// Build the error of a graph with loop, naming the nodes of the loop.
+    fn loop_error(&self) -> GraphError {
+        let names = self
+            .find_cycle()
+            .unwrap_or_default()
+            .iter()
+            .map(|id| self.nodes[id].name())
+            .collect();
+        GraphError::GraphLoopDetected(names)
+    }
+
+    /// Get the distinct successors of each node from the edges. Unlike the output channels,
+    /// the edges are kept when the channels are closed by a run.
+    fn successors(&self) -> HashMap<NodeId, HashSet<NodeId>> {
+        let mut successors: HashMap<NodeId, HashSet<NodeId>> = HashMap::new();
+        for (from_id, to_ids) in &self.edges {
+            successors
+                .entry(*from_id)
+                .or_default()
+                .extend(to_ids.iter().copied());
+        }
+        successors
+    }
+
+    /// Get the distinct predecessors of each node from the edges.
+    fn predecessors(&self) -> HashMap<NodeId, Vec<NodeId>> {
+        let mut predecessors: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for (from_id, to_ids) in self.successors() {
+            for to_id in to_ids {
+                predecessors.entry(to_id).or_default().push(from_id);
+            }
+        }
+        predecessors
+    }
+
+    /// Get the fraction of the nodes resolved in the current run, counting both the
+    /// succeeded and the failed nodes.
+    pub fn progress(&self) -> f32 {
+        self.progress.fraction()
+    }
+
+    /// Get a handle to the progress of this graph, which can be polled from another task
+    /// while the graph is running. See [`Graph::progress`].
+    pub fn progress_handle(&self) -> Progress {
+        self.progress.clone()
+    }
+
+    /// Get the state of every node in the current run, see [`NodeState`]. The map is empty
+    /// before the graph is started.
+    pub fn node_states(&self) -> HashMap<NodeId, NodeState> {
+        self.states.snapshot()
+    }
+
+    /// Get a handle to the states of the nodes of this graph, which can be polled from
+    /// another task while the graph is running. See [`Graph::node_states`].
+    pub fn node_states_handle(&self) -> NodeStates {
+        self.states.clone()
+    }
+
+    /// Get the output of all tasks.
+    pub fn get_results<T: Send + Sync + 'static>(&self) -> HashMap<NodeId, Option<Arc<T>>> {
+        self.execute_states
+            .iter()
+            .map(|(&id, state)| {
+                let output = match state.get_output() {
+                    Some(content) => content.into_inner(),
+                    None => None,
+                };
+                (id, output)
+            })
+            .collect()
+    }
+
+    /// Get the error message of each failed node. The nodes that succeeded or did not run
+    /// are absent.
+    pub fn get_errors(&self) -> HashMap<NodeId, String> {
+        self.execute_states
+            .iter()
+            .filter_map(|(&id, state)| Some((id, state.get_full_output().get_err()?)))
+            .collect()
+    }
+
+    /// Get the result of the node with the given id. Returns `None` if the node did not
+    /// produce an output of type `T`, e.g. when it failed or was not run.
+    pub fn get_result<T: Send + Sync + 'static>(&self, id: &NodeId) -> Option<Arc<T>> {
+        self.execute_states.get(id)?.get_output()?.into_inner()
+    }
+
+    /// Get the result of the node named `name`. See [`Graph::get_result`].
+    ///
+    /// If several nodes are named `name`, the one created last is used, as in the
+    /// [`NodeTable`].
+    pub fn get_result_by_name<T: Send + Sync + 'static>(&self, name: &str) -> Option<Arc<T>> {
+        let id = self
+            .nodes
+            .values()
+            .filter(|node| node.name() == name)
+            .map(|node| node.id())
+            .max_by_key(|id| id.0)?;
+        self.get_result(&id)
+    }
+
+    /// Get the results of type `T` of the nodes without successors that succeeded.
+    pub fn get_leaf_results<T: Send + Sync + 'static>(&self) -> HashMap<NodeId, Arc<T>> {
+        self.leaves()
+            .into_iter()
+            .filter_map(|id| {
+                let content = self.execute_states.get(&id)?.get_output()?;
+                Some((id, content.into_inner()?))
+            })
+            .collect()
+    }
+
+    /// Get the nodes without successors, sorted by id.
+    pub fn leaves(&self) -> Vec<NodeId> {
+        let successors = self.successors();
+        let mut leaves: Vec<NodeId> = self
+            .nodes
+            .keys()
+            .filter(|id| successors.get(id).is_none_or(|ids| ids.is_empty()))
+            .copied()
+            .collect();
+        leaves.sort_by_key(|id| id.0);
+        leaves
+    }
+
+    /// Iterate over the nodes, sorted by id, e.g. to inspect the graph before it runs. See
+    /// [`Graph::predecessors_of`] for the dependencies of a node.
+    pub fn iter_nodes(&self) -> impl Iterator<Item = &dyn Node> {
+        let mut nodes: Vec<&dyn Node> = self.nodes.values().map(|node| node.as_ref()).collect();
+        nodes.sort_by_key(|node| node.id());
+        nodes.into_iter()
+    }
+
+    /// Get the nodes depending on the node with the given id, directly or not, sorted by id,
+    /// i.e. the nodes affected if it fails.
+    pub fn descendants(&self, id: &NodeId) -> Vec<NodeId> {
+        let mut descendants: Vec<NodeId> = reachable(*id, &self.successors())
+            .into_iter()
+            .filter(|descendant| descendant != id)
+            .collect();
+        descendants.sort();
+        descendants
+    }
+
+    /// Get the nodes the node with the given id depends on, directly or not, sorted by id.
+    pub fn ancestors(&self, id: &NodeId) -> Vec<NodeId> {
+        let mut ancestors: Vec<NodeId> = reachable(*id, &self.predecessors())
+            .into_iter()
+            .filter(|ancestor| ancestor != id)
+            .collect();
+        ancestors.sort();
+        ancestors
+    }
+
+    /// Get the names of the nodes depending on the node named `name`, directly or not. See
+    /// [`Graph::descendants`]. Returns [`GraphError::NodeNotFound`] if no node is named `name`.
+    pub fn downstream_of(&self, name: &str) -> Result<Vec<NodeName>, GraphError> {
+        let id = self.id_of(name)?;
+        Ok(self
+            .descendants(&id)
+            .iter()
+            .map(|id| self.nodes[id].name())
+            .collect())
+    }
+
+    /// Get the names of the nodes the node named `name` depends on, directly or not. See
+    /// [`Graph::ancestors`]. Returns [`GraphError::NodeNotFound`] if no node is named `name`.
+    pub fn upstream_of(&self, name: &str) -> Result<Vec<NodeName>, GraphError> {
+        let id = self.id_of(name)?;
+        Ok(self
+            .ancestors(&id)
+            .iter()
+            .map(|id| self.nodes[id].name())
+            .collect())
+    }
+
+    /// Get the distinct predecessors of the node with the given id, sorted by id.
+    pub fn predecessors_of(&self, id: &NodeId) -> Vec<NodeId> {
+        let mut predecessors: Vec<NodeId> = self
+            .successors()
+            .into_iter()
+            .filter(|(_, to_ids)| to_ids.contains(id))
+            .map(|(from_id, _)| from_id)
+            .collect();
+        predecessors.sort();
+        predecessors
+    }
+
+    /// Get the number of attempts made to run each node.
+    pub fn get_attempts(&self) -> HashMap<NodeId, u32> {
+        self.execute_states
+            .iter()
+            .map(|(&id, state)| (id, state.attempts()))
+            .collect()
+    }
+
+    /// Get how long each node took to run, summed over all its attempts. The waits before and
+    /// between the attempts, e.g. for a permit, a resource or a retry delay, are not counted.
+    /// The nodes that never ran, e.g. the skipped ones, are absent.
+    pub fn get_durations(&self) -> HashMap<NodeId, Duration> {
+        self.execute_states
+            .iter()
+            .filter_map(|(&id, state)| Some((id, state.duration()?)))
+            .collect()
+    }
+
+    /// Get the longest path through the graph, weighted by the given duration of each node,
+    /// from its first node to its last. The nodes without a duration weigh nothing. Among
+    /// paths of the same length, the one ending last in the execution order is chosen, going
+    /// through the predecessors of the smallest ids.
+    /// See [`Graph::dry_run`] for the errors.
+    pub fn critical_path(
+        &self,
+        durations: &HashMap<NodeId, Duration>,
+    ) -> Result<Vec<NodeId>, GraphError> {
+        self.validate()?;
+        let sequence = self.topo_sort().ok_or_else(|| self.loop_error())?;
+        let predecessors = self.predecessors();
+        // The length of the longest path ending at each node, and the node before it.
+        let mut longest: HashMap<NodeId, (Duration, Option<NodeId>)> = HashMap::new();
+        let mut end: Option<(Duration, NodeId)> = None;
+        for id in sequence {
+            let (before, previous) = predecessors
+                .get(&id)
+                .into_iter()
+                .flatten()
+                .map(|p| (longest[p].0, Some(*p)))
+                .max_by_key(|(length, p)| (*length, Reverse(*p)))
+                .unwrap_or_default();
+            let length = before + durations.get(&id).copied().unwrap_or_default();
+            longest.insert(id, (length, previous));
+            if end.is_none_or(|(max, _)| length >= max) {
+                end = Some((length, id));
+            }
+        }
+        let mut path: Vec<NodeId> =
+            std::iter::successors(end.map(|(_, id)| id), |id| longest[id].1).collect();
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Get the names of the nodes on the critical path of the last run, weighted by how long
+    /// each node took. The nodes that did not run are excluded. See [`Graph::critical_path`].
+    pub fn critical_path_after_run(&self) -> Result<Vec<NodeName>, GraphError> {
+        let durations = self.get_durations();
+        Ok(self
+            .critical_path(&durations)?
+            .iter()
+            .filter(|id| durations.contains_key(id))
+            .map(|id| self.nodes[id].name())
+            .collect())
+    }
+
+    /// Get the nodes whose last attempt to run timed out. Timed out nodes are failed nodes, so
+    /// they have no results.
+    pub fn get_timed_out(&self) -> HashSet<NodeId> {
+        self.execute_states
+            .iter()
+            .filter(|(_, state)| state.timed_out())
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Get the report of the last run, with the status, the timing, the attempts and the
+    /// error of each node. See [`ExecutionReport`].
+    pub fn execution_report(&self) -> ExecutionReport {
+        let mut nodes: Vec<NodeReport> = self
+            .execute_states
+            .iter()
+            .map(|(&id, state)| {
+                let output = state.get_full_output();
+                let status = if state.cancelled() {
+                    NodeStatus::Cancelled
+                } else if state.skipped() {
+                    NodeStatus::Skipped
+                } else if output.is_err() {
+                    NodeStatus::Failed
+                } else if state.success() {
+                    NodeStatus::Succeeded
+                } else {
+                    NodeStatus::NotRun
+                };
+                let timing = state.timing();
+                NodeReport {
+                    id,
+                    name: self
+                        .nodes
+                        .get(&id)
+                        .map(|node| node.name())
+                        .unwrap_or_default(),
+                    status,
+                    start: timing.map(|(start, _)| start),
+                    duration: timing.map(|(_, duration)| duration),
+                    attempts: state.attempts(),
+                    error: output.get_err(),
+                }
+            })
+            .collect();
+        nodes.sort_by_key(|node| node.id.0);
+        let start = nodes.iter().filter_map(|node| node.start).min();
+        let end = nodes.iter().filter_map(NodeReport::end).max();
+        let wall_time = start.zip(end).map(|(start, end)| end - start);
+        ExecutionReport {
+            nodes,
+            wall_time,
+            deadline_exceeded: self.deadline_exceeded(),
+        }
+    }
+
+    /// Get the nodes skipped without running: the ones whose condition is false, and the ones
+    /// whose output is stored or cached. Skipped nodes are successful nodes.
+    pub fn get_skipped(&self) -> HashSet<NodeId> {
+        self.execute_states
+            .iter()
+            .filter(|(_, state)| state.skipped())
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Get the full output of all tasks.
+    pub fn get_outputs(&self) -> HashMap<NodeId, Output> {
+        self.execute_states
+            .iter()
+            .map(|(&id, state)| {
+                let t = state.get_full_output();
+                (id, t)
+            })
+            .collect()
+    }
+
+    /// Limit the number of nodes running at the same time to `n`. A limit of 0 is rejected with
+    /// [`GraphError::InvalidParallelism`], and a limit of `usize::MAX` means no limit.
+    ///
+    /// A node only holds a permit while it runs: it gives it back while it waits for its
+    /// dependencies, or to send or receive a content through its channels, and gets it again
+    /// afterwards. So a node streaming more contents than its channels hold does not keep its
+    /// successors from receiving them. A broadcast send never waits though, so a successor
+    /// waiting for a permit may miss the contents of a node sending to several successors.
+    pub fn set_max_parallelism(&mut self, n: usize) -> Result<(), GraphError> {
+        if n == 0 {
+            return Err(GraphError::InvalidParallelism(n));
+        }
+        // Limits beyond the permits a semaphore can hold cannot be reached anyway.
+        self.max_parallelism = (n < Semaphore::MAX_PERMITS).then_some(n);
+        Ok(())
+    }
+
+    /// Set the capacity of the resource pool `name`. The nodes requiring some of the resource
+    /// run only while the pool holds enough for them, see [`Node::resources`]. A node
+    /// requiring more than the capacity fails the start with [`GraphError::InvalidResource`].
+    pub fn set_resource(&mut self, name: &str, capacity: u32) {
+        self.resources.insert(name.to_string(), capacity);
+    }
+
+    /// Start at most `rate` attempts to run the nodes within any period of length `per`. The
+    /// attempts beyond wait for their turn, unless the graph is aborted. A rate of 0 is
+    /// [`GraphError::InvalidRate`].
+    pub fn set_start_rate(&mut self, rate: u32, per: Duration) -> Result<(), GraphError> {
+        if rate == 0 {
+            return Err(GraphError::InvalidRate(rate));
+        }
+        self.start_rate = Some((rate, per));
+        Ok(())
+    }
+
+    /// Builder style version of [`Graph::set_max_parallelism`].
+    pub fn with_max_concurrency(mut self, n: usize) -> Result<Self, GraphError> {
+        self.set_max_parallelism(n)?;
+        Ok(self)
+    }
+
+    /// Catch the panics of the nodes quietly, without printing them by the panic hook. The panic
+    /// messages are still logged and stored as the errors of the nodes.
+    pub fn set_quiet_panics(&mut self, quiet: bool) {
+        self.quiet_panics = quiet;
+    }
+
+    /// Cache the outputs of the nodes with a cache key in `cache`, so that these nodes are
+    /// skipped once their outputs are cached. See [`Node::cache_key`].
+    pub fn set_cache(&mut self, cache: Arc<dyn Cache>) {
+        self.cache = Some(cache);
+    }
+
+    /// Save the nodes completed successfully in the last run to a checkpoint file at `path`,
+    /// see [`Graph::resume_from`]. The nodes are saved by their names, so an error of kind
+    /// [`io::ErrorKind::InvalidInput`] is returned if several nodes have the same name.
+    pub fn checkpoint_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(name) = self.duplicate_name() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                GraphError::DuplicateName(name),
+            ));
+        }
+        let completed = self
+            .execute_states
+            .iter()
+            .filter(|(_, state)| state.success())
+            .map(|(id, state)| (self.nodes[id].name(), state.get_full_output()))
+            .collect();
+        checkpoint::save(path.as_ref(), completed)
+    }
+
+    /// Resume from the checkpoint file at `path` in the next run. The nodes completed in the
+    /// checkpoint, matched by their names, are skipped if their outputs can be restored, and
+    /// the restored outputs are sent to their successors. Starting the graph returns
+    /// [`GraphError::DuplicateName`] if several nodes have the same name.
+    ///
+    /// Only the outputs of type [`String`], the empty outputs and the maps of named
+    /// [`String`]s can be restored, the nodes with other outputs run again.
+    pub fn resume_from(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.restored = checkpoint::load(path.as_ref())?;
+        Ok(())
+    }
+
+    /// Abort a run of the graph that takes longer than `deadline`, as if by a
+    /// [`GraphAbortHandle`]: the nodes still running are cancelled and the run returns
+    /// `Ok(false)`.
+    pub fn set_deadline(&mut self, deadline: Duration) {
+        self.deadline = Some(deadline);
+    }
+
+    /// Abort the graph on SIGINT or SIGTERM, as if by a [`GraphAbortHandle`]: the nodes still
+    /// running are cancelled, and the run returns `Ok(false)` with the results of the
+    /// completed nodes intact.
+    ///
+    /// The signals are only listened to while the graph runs. Once the run ends, they are
+    /// handled as they were before, e.g. a signal terminates the process again.
+    #[cfg(all(unix, feature = "signal"))]
+    pub fn handle_signals(&mut self, enable: bool) {
+        self.handle_signals = enable;
+    }
+
+    /// Whether the last run was aborted by its deadline. See [`Graph::set_deadline`].
+    pub fn deadline_exceeded(&self) -> bool {
+        self.deadline_exceeded
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Get a handle to abort the graph while it runs. See [`GraphAbortHandle`].
+    pub fn abort_handle(&self) -> GraphAbortHandle {
+        GraphAbortHandle::new(Arc::clone(&self.is_active), Arc::clone(&self.aborted))
+    }
+
+    /// Get a handle to pause and resume the graph while it runs. See [`GraphPauseHandle`].
+    pub fn pause_handle(&self) -> GraphPauseHandle {
+        GraphPauseHandle::new(Arc::clone(&self.paused))
+    }
+
+    /// Spawn the futures running the nodes with `executor`, instead of [`TokioExecutor`].
+    pub fn set_executor(&mut self, executor: Arc<dyn NodeExecutor>) {
+        self.executor = executor;
+    }
+
+    /// Send the records logged by the nodes through their [`NodeLogger`] to `sink`, instead
+    /// of the global logger.
+    pub fn set_log_sink(&mut self, sink: Arc<dyn LogSink>) {
+        self.log_sink = Some(sink);
+    }
+
+    /// Register the observer notified of the lifecycle events of the nodes and the graph,
+    /// replacing the observers registered before.
+    pub fn set_observer(&mut self, observer: Arc<dyn GraphObserver>) {
+        self.observers = vec![observer];
+    }
+
+    /// Register another observer notified of the lifecycle events of the nodes and the graph.
+    /// The observers are notified in the order they are added.
+    pub fn add_observer(&mut self, observer: Arc<dyn GraphObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Use `output` as the output of the node named `name` in every run, instead of running
+    /// the node. The output is sent to the successors of the node like the output of a
+    /// skipped node, see [`Node::cache_key`].
+    pub fn set_precomputed(&mut self, name: &str, output: Output) {
+        self.precomputed.insert(name.to_string(), output);
+    }
+
+    /// Before the dag starts executing, set the dag's global environment variable.
+    pub fn set_env(&mut self, env: EnvVar) {
+        self.env = Arc::new(env);
+    }
+
+    /// Share the given environment variables, e.g. those of an outer graph.
+    pub(crate) fn set_shared_env(&mut self, env: Arc<EnvVar>) {
+        self.env = env;
+    }
+
+    /// Remove duplicate elements
+    fn remove_duplicates<T>(vec: Vec<T>) -> Vec<T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let mut seen = HashSet::new();
+        vec.into_iter().filter(|x| seen.insert(x.clone())).collect()
+    }
+}
+
+/// A node in the plan returned by [`Graph::dry_run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedNode {
+    pub id: NodeId,
+    pub name: NodeName,
+    /// The names of the predecessors, in execution order.
+    pub predecessors: Vec<NodeName>,
+}
+
+/// The report of a run, returned by [`Graph::execution_report`].
+#[derive(Debug, Clone)]
+pub struct ExecutionReport {
+    /// The reports of the nodes, sorted by id.
+    pub nodes: Vec<NodeReport>,
+    /// The time from the start of the first node to the end of the last one, `None` if no
+    /// node ran.
+    pub wall_time: Option<Duration>,
+    /// Whether the run was aborted by its deadline, see [`Graph::set_deadline`].
+    pub deadline_exceeded: bool,
+}
+
+impl ExecutionReport {
+    /// Whether the run succeeded, i.e. no node failed or was cancelled.
+    pub fn succeeded(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| !matches!(node.status, NodeStatus::Failed | NodeStatus::Cancelled))
+    }
+
+    /// Get the number of nodes with the given status.
+    pub fn count(&self, status: NodeStatus) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.status == status)
+            .count()
+    }
+
+    /// Get the report of the node with the given id.
+    pub fn get(&self, id: &NodeId) -> Option<&NodeReport> {
+        self.nodes.iter().find(|node| node.id == *id)
+    }
+}
+
+/// The report of a node in an [`ExecutionReport`].
+#[derive(Debug, Clone)]
+pub struct NodeReport {
+    pub id: NodeId,
+    pub name: NodeName,
+    pub status: NodeStatus,
+    /// When the node started running, `None` if it never ran.
+    pub start: Option<Instant>,
+    /// How long the node took to run, including all its attempts.
+    pub duration: Option<Duration>,
+    /// The number of attempts made to run the node.
+    pub attempts: u32,
+    /// The error of the node, if it failed.
+    pub error: Option<String>,
+}
+
+impl NodeReport {
+    /// When the node stopped running, `None` if it never ran.
+    pub fn end(&self) -> Option<Instant> {
+        Some(self.start? + self.duration?)
+    }
+
+    /// The wall-clock time when the node started running, `None` if it never ran.
+    pub fn start_time(&self) -> Option<SystemTime> {
+        Some(SystemTime::now() - self.start?.elapsed())
+    }
+}
+
+/// The final status of a node in an [`ExecutionReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Succeeded,
+    Failed,
+    /// The node was cancelled because the graph was aborted.
+    Cancelled,
+    /// The node was skipped without running, see [`Graph::get_skipped`].
+    Skipped,
+    /// The node was not part of the run, e.g. when starting only a target.
+    NotRun,
+}
+
+/// Marks a node as finished when dropped, even if it panics or is aborted.
+struct FinishGuard(Arc<ExecState>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.mark_finished();
+    }
+}
+
+/// Stops the timers of a run when dropped, i.e. when the run ends or is cancelled.
+struct StopGuard(watch::Sender<bool>);
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        self.0.send_replace(true);
+    }
+}
+
+/// A locked node, whose output channels are closed when the guard is dropped. The node is
+/// only taken out of its lock once the run ends.
+struct ClosingGuard(OwnedMutexGuard<Option<Box<dyn Node>>>);
+
+impl Deref for ClosingGuard {
+    type Target = Box<dyn Node>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for ClosingGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut().unwrap()
+    }
+}
+
+impl Drop for ClosingGuard {
+    fn drop(&mut self) {
+        if let Some(node) = self.0.as_mut() {
+            node.output_channels().close_all();
+        }
+    }
+}
+
+/// Get the nodes reachable from `start` by following `edges`, not including `start` unless it
+/// is on a loop.
+fn reachable<C>(start: NodeId, edges: &HashMap<NodeId, C>) -> HashSet<NodeId>
+where
+    for<'a> &'a C: IntoIterator<Item = &'a NodeId>,
+{
+    let mut reached = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(id) = stack.pop() {
+        for next in edges.get(&id).into_iter().flatten() {
+            if reached.insert(*next) {
+                stack.push(*next);
+            }
+        }
+    }
+    reached
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::default_node::DefaultNode;
+    use crate::{
+        Action, Content, EnvVar, InChannels, Node, NodeName, NodeTable, OutChannels, Output,
+        RetryBackoff, RetryPolicy, SampledObserver,
+    };
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// An implementation of [`Action`] that returns [`Output::Out`] containing a String "Hello world" from default_node.rs.
+    #[derive(Default)]
+    pub struct HelloAction;
+    #[async_trait]
+    impl Action for HelloAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            Output::Out(Some(Content::new("Hello world".to_string())))
+        }
+    }
+
+    impl HelloAction {
+        pub fn new() -> Box<Self> {
+            Box::new(Self)
+        }
+    }
+
+    /// An implementation of [`Action`] that sleeps for the given duration.
+    pub struct SleepAction(Duration);
+    #[async_trait]
+    impl Action for SleepAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            tokio::time::sleep(self.0).await;
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that records its label when it starts, then waits
+    /// for the given node (if any) to close its channel.
+    pub struct LogAction {
+        label: &'static str,
+        log: Arc<std::sync::Mutex<Vec<&'static str>>>,
+        recv: Option<NodeId>,
+    }
+    #[async_trait]
+    impl Action for LogAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            self.log.lock().unwrap().push(self.label);
+            if let Some(id) = self.recv {
+                let _ = in_channels.recv_from(&id).await;
+            }
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that sleeps for a while, recording the number of
+    /// actions running at the same time and the maximum of it.
+    pub struct CountAction {
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+    #[async_trait]
+    impl Action for CountAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that fails until it has been run `succeed_at` times.
+    pub struct FlakyAction {
+        runs: Arc<AtomicUsize>,
+        succeed_at: usize,
+    }
+    #[async_trait]
+    impl Action for FlakyAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            let runs = self.runs.fetch_add(1, Ordering::SeqCst) + 1;
+            if runs < self.succeed_at {
+                Output::error(format!("failed at run {}", runs))
+            } else {
+                Output::new(runs)
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that returns the environment variable "factor" doubled.
+    pub struct DoubleAction;
+    #[async_trait]
+    impl Action for DoubleAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            Output::new(env.get::<usize>("factor").unwrap() * 2)
+        }
+    }
+
+    /// An implementation of [`Action`] that returns the product of the environment variables
+    /// "factor" and "scale".
+    pub struct ScaleAction;
+    #[async_trait]
+    impl Action for ScaleAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            Output::new(env.get::<usize>("factor").unwrap() * env.get::<usize>("scale").unwrap())
+        }
+    }
+
+    /// An implementation of [`Action`] that outputs a count and a label by names, and sends
+    /// them to its successors.
+    pub struct CountAndLabelAction;
+    #[async_trait]
+    impl Action for CountAndLabelAction {
+        async fn run(
+            &self,
+            _: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let out = Output::map([
+                ("count", Content::new(3usize)),
+                ("label", Content::new("ok".to_string())),
+            ]);
+            out_channels
+                .send_to_all(out.get_out().unwrap())
+                .await
+                .unwrap();
+            out
+        }
+    }
+
+    /// An implementation of [`Action`] that outputs the content named by the key it is given,
+    /// received from its only predecessor.
+    pub struct NamedAction<H>(&'static str, std::marker::PhantomData<H>);
+    #[async_trait]
+    impl<H: Clone + Send + Sync + 'static> Action for NamedAction<H> {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let id = *in_channels.channels.keys().next().unwrap();
+            let content = in_channels.recv_from(&id).await.unwrap();
+            Output::new(content.get_named::<H>(self.0).unwrap().clone())
+        }
+    }
+
+    /// An implementation of [`Action`] that publishes the environment variable "token", and
+    /// notifies its successors.
+    pub struct PublishAction;
+    #[async_trait]
+    impl Action for PublishAction {
+        async fn run(
+            &self,
+            _: &mut InChannels,
+            out_channels: &OutChannels,
+            env: Arc<EnvVar>,
+        ) -> Output {
+            env.set_shared("token", "secret".to_string());
+            out_channels.send_to_all(Content::new(())).await.unwrap();
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that waits for its predecessors, and outputs the
+    /// environment variable "token" if it is set.
+    pub struct ReadTokenAction;
+    #[async_trait]
+    impl Action for ReadTokenAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            env: Arc<EnvVar>,
+        ) -> Output {
+            let predecessors: Vec<NodeId> = in_channels.channels.keys().copied().collect();
+            for id in predecessors {
+                in_channels.recv_from(&id).await.unwrap();
+            }
+            env.set_shared("reader", true);
+            match env.get::<String>("token") {
+                Some(token) => Output::new(token),
+                None => Output::empty(),
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that reads the environment variable "base" many
+    /// times, and outputs the sum.
+    pub struct ReadBaseAction;
+    #[async_trait]
+    impl Action for ReadBaseAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            let mut sum = 0;
+            for _ in 0..100 {
+                sum += env.get::<usize>("base").unwrap();
+                tokio::task::yield_now().await;
+            }
+            Output::new(sum)
+        }
+    }
+
+    /// An implementation of [`Action`] that publishes and removes the environment variable
+    /// of the given name many times, leaving it published.
+    pub struct WriteSharedAction(&'static str);
+    #[async_trait]
+    impl Action for WriteSharedAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            for i in 0..100usize {
+                env.set_shared(self.0, i);
+                assert_eq!(env.get::<usize>(self.0), Some(i));
+                assert!(env.remove(self.0));
+                tokio::task::yield_now().await;
+            }
+            env.set_shared(self.0, 100usize);
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that increments the published counter "count" many
+    /// times.
+    pub struct IncrementAction;
+    #[async_trait]
+    impl Action for IncrementAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            for _ in 0..100 {
+                env.update_shared("count", |count: Option<&usize>| count.map_or(1, |c| c + 1));
+                tokio::task::yield_now().await;
+            }
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that sends a String of the given length to its
+    /// successors, and fails if it cannot.
+    pub struct SendRepeatAction(usize);
+    #[async_trait]
+    impl Action for SendRepeatAction {
+        async fn run(&self, _: &mut InChannels, out: &OutChannels, _: Arc<EnvVar>) -> Output {
+            match out.send_to_all(Content::sized("x".repeat(self.0))).await {
+                Ok(()) => Output::empty(),
+                Err(e) => Output::error(format!("{:?}", e)),
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that returns a String of the given length.
+    pub struct RepeatAction(usize);
+    #[async_trait]
+    impl Action for RepeatAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            Output::sized("x".repeat(self.0))
+        }
+    }
+
+    /// An implementation of [`Action`] that panics.
+    pub struct PanicAction;
+    #[async_trait]
+    impl Action for PanicAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            panic!("oops")
+        }
+    }
+
+    /// An implementation of [`Action`] that sends a String "Hello world" to all its successors.
+    pub struct SendAction;
+    #[async_trait]
+    impl Action for SendAction {
+        async fn run(
+            &self,
+            _: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            for id in out_channels.channels.keys() {
+                let content = Content::new("Hello world".to_string());
+                if let Err(e) = out_channels.send_to(id, content).await {
+                    return Output::error(format!("{:?}", e));
+                }
+            }
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that counts its runs, and both sends and returns a
+    /// String "Hello world".
+    pub struct CountedHelloAction(Arc<AtomicUsize>);
+    #[async_trait]
+    impl Action for CountedHelloAction {
+        async fn run(
+            &self,
+            _: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let content = Content::new("Hello world".to_string());
+            match out_channels.send_to_all(content.clone()).await {
+                Ok(()) => Output::Out(Some(content)),
+                Err(e) => Output::error(format!("{:?}", e)),
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that returns the content received from the given node.
+    pub struct RecvAction(NodeId);
+    #[async_trait]
+    impl Action for RecvAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            match in_channels.recv_from(&self.0).await {
+                Ok(content) => Output::Out(Some(content)),
+                Err(e) => Output::error(format!("{:?}", e)),
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that returns how many contents it receives from the
+    /// given nodes, ignoring the closed channels.
+    pub struct RecvAllAction(Vec<NodeId>);
+    #[async_trait]
+    impl Action for RecvAllAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let mut count = 0;
+            for id in &self.0 {
+                if in_channels.recv_from(id).await.is_ok() {
+                    count += 1;
+                }
+            }
+            Output::new(count)
         }
     }
 
     /// Test for execute a graph.
     ///
-    /// Step 1: create a graph and two DefaultNode.
+    /// Step 1: create a graph and two DefaultNode.
+    ///
+    /// Step 2: add the nodes to graph.
+    ///
+    /// Step 3: add the edge between Node X and "Node Y.
+    ///
+    /// Step 4: Run the graph and verify the output saved in the graph structure.
+    #[test]
+    fn test_graph_execution() {
+        env_logger::init();
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node_name = "Node X";
+        let node = DefaultNode::new(NodeName::from(node_name), &mut node_table);
+        let node_id = node.id();
+
+        let node1_name = "Node Y";
+        let node1 = DefaultNode::with_action(
+            NodeName::from(node1_name),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+
+        graph.add_edge(node_id, vec![node1_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        let out = graph.execute_states[&node1_id].get_output().unwrap();
+        let out: &String = out.get().unwrap();
+        assert_eq!(out, "Hello world");
+    }
+
+    /// Test for the deprecated synchronous `run`, which still runs the graph.
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_run() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        graph.run();
+        let out = graph.execute_states[&node_id].get_output().unwrap();
+        let out: &String = out.get().unwrap();
+        assert_eq!(out, "Hello world");
+    }
+
+    /// Test for starting a graph on an existing runtime.
+    ///
+    /// `start_async` is awaited inside a task spawned on a multi-thread runtime, which
+    /// must not panic as building a nested runtime would.
+    #[test]
+    fn test_start_async_in_runtime() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let graph = runtime.block_on(async move {
+            tokio::spawn(async move {
+                assert!(matches!(graph.start_async().await, Ok(true)));
+                graph
+            })
+            .await
+            .unwrap()
+        });
+
+        let out = graph.execute_states[&node_id].get_output().unwrap();
+        let out: &String = out.get().unwrap();
+        assert_eq!(out, "Hello world");
+    }
+
+    /// Test for a node running longer than its timeout.
+    ///
+    /// Node X sleeps for 500ms with a timeout of 100ms, and Node Y waits for a message from
+    /// Node X. Node X is cancelled and fails, Node Y sees the closed channel and fails as well,
+    /// but only Node X is reported as timed out.
+    #[test]
+    fn test_node_timeout() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_millis(500))),
+            &mut node_table,
+        )
+        .with_timeout(Duration::from_millis(100));
+        let node_id = node.id();
+
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        node1.set_timeout(Duration::from_secs(1));
+        let node1_id = node1.id();
+
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        let begin = Instant::now();
+        assert!(matches!(graph.start(), Ok(false)));
+        assert!(begin.elapsed() < Duration::from_millis(500));
+
+        let outputs = graph.get_outputs();
+        assert_eq!(
+            outputs[&node_id].get_err().unwrap(),
+            GraphError::NodeTimeout("Node X".to_string()).to_string()
+        );
+        assert!(outputs[&node1_id].is_err());
+        assert_eq!(graph.get_timed_out(), HashSet::from([node_id]));
+        assert!(graph.get_results::<()>()[&node_id].is_none());
+    }
+
+    /// Test for lazy nodes.
+    ///
+    /// Lazy Node X has a single successor Node Y, so it runs only after Node Y starts.
+    /// Lazy Node Z has two successors, so it runs eagerly without deadlock.
+    #[test]
+    fn test_lazy_node() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let node_x = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(LogAction {
+                label: "X",
+                log: log.clone(),
+                recv: None,
+            }),
+            &mut node_table,
+        )
+        .lazy();
+        let x_id = node_x.id();
+        let node_y = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(LogAction {
+                label: "Y",
+                log: log.clone(),
+                recv: Some(x_id),
+            }),
+            &mut node_table,
+        );
+        let y_id = node_y.id();
+        let node_z = DefaultNode::new(NodeName::from("Node Z"), &mut node_table).lazy();
+        let z_id = node_z.id();
+
+        graph.add_node(Box::new(node_x));
+        graph.add_node(Box::new(node_y));
+        graph.add_node(Box::new(node_z));
+        graph.add_edge(x_id, vec![y_id]);
+        graph.add_edge(z_id, vec![x_id, y_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*log.lock().unwrap(), vec!["Y", "X"]);
+    }
+
+    /// Test for starting graphs on a caller-provided runtime.
+    ///
+    /// Two graphs run on the same runtime in turn. Blocking on a graph from inside the
+    /// runtime is rejected with an error instead of panicking.
+    #[test]
+    fn test_start_on_shared_runtime() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut node_table = NodeTable::new();
+
+        for _ in 0..2 {
+            let mut graph = Graph::new();
+            let node = DefaultNode::with_action(
+                NodeName::from("Node X"),
+                HelloAction::new(),
+                &mut node_table,
+            );
+            graph.add_node(Box::new(node));
+            assert!(matches!(graph.start_on(runtime.handle()), Ok(true)));
+        }
+
+        let mut graph = Graph::new();
+        let res = runtime.block_on(async { graph.start() });
+        assert!(matches!(res, Err(GraphError::BlockingInAsyncContext)));
+    }
+
+    /// Test for limiting the parallelism of a graph.
+    ///
+    /// Four independent nodes run with a limit of 1 and 2, and the maximum number of nodes
+    /// running at the same time never exceeds the limit. A chain of two nodes still completes
+    /// with a limit of 1.
+    #[test]
+    fn test_max_parallelism() {
+        for limit in [1, 2] {
+            let mut graph = Graph::new();
+            let mut node_table = NodeTable::new();
+            let running = Arc::new(AtomicUsize::new(0));
+            let max_running = Arc::new(AtomicUsize::new(0));
+
+            for i in 0..4 {
+                let node = DefaultNode::with_action(
+                    format!("Node {}", i),
+                    Box::new(CountAction {
+                        running: running.clone(),
+                        max_running: max_running.clone(),
+                    }),
+                    &mut node_table,
+                );
+                graph.add_node(Box::new(node));
+            }
+            let node = DefaultNode::with_action(
+                NodeName::from("Node X"),
+                HelloAction::new(),
+                &mut node_table,
+            );
+            let node_id = node.id();
+            let node1 = DefaultNode::with_action(
+                NodeName::from("Node Y"),
+                Box::new(RecvAction(node_id)),
+                &mut node_table,
+            );
+            let node1_id = node1.id();
+            graph.add_node(Box::new(node));
+            graph.add_node(Box::new(node1));
+            graph.add_edge(node_id, vec![node1_id]);
+
+            graph.set_max_parallelism(limit).unwrap();
+            graph.start().unwrap();
+            assert_eq!(max_running.load(Ordering::SeqCst), limit);
+        }
+    }
+
+    /// An implementation of [`Action`] that adds 1 to the number received from its only
+    /// predecessor, or outputs 0 without one, and sends the result to its successors.
+    pub struct IncrementChainAction;
+    #[async_trait]
+    impl Action for IncrementChainAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let predecessor = in_channels.channels.keys().next().copied();
+            let n = match predecessor {
+                Some(id) => {
+                    let content = in_channels.recv_from(&id).await.unwrap();
+                    *content.get::<usize>().unwrap() + 1
+                }
+                None => 0,
+            };
+            out_channels.send_to_all(Content::new(n)).await.unwrap();
+            Output::new(n)
+        }
+    }
+
+    /// Test for a chain of nodes longer than the maximum parallelism.
+    ///
+    /// Each of the 6 nodes adds 1 to the number sent by the previous one, with at most 2
+    /// nodes running at the same time, so the last node outputs 5.
+    #[test]
+    fn test_max_parallelism_chain() {
+        let mut node_table = NodeTable::new();
+        let nodes: Vec<DefaultNode> = (0..6)
+            .map(|i| {
+                DefaultNode::with_action(
+                    format!("Node {}", i),
+                    Box::new(IncrementChainAction),
+                    &mut node_table,
+                )
+            })
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|node| node.id()).collect();
+        let mut graph = Graph::new();
+        for node in nodes {
+            graph.add_node(Box::new(node));
+        }
+        for pair in ids.windows(2) {
+            graph.add_edge(pair[0], vec![pair[1]]);
+        }
+
+        graph.set_max_parallelism(2).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<usize>(&ids[5]).unwrap(), 5);
+    }
+
+    /// An implementation of [`Action`] that logs its label through its node logger.
+    pub struct NodeLogAction(&'static str);
+    #[async_trait]
+    impl Action for NodeLogAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            NodeLogger::current().unwrap().info(self.0);
+            Output::empty()
+        }
+    }
+
+    /// Test for routing the records logged by the nodes to a log sink.
+    ///
+    /// Node X and Node Y each log a record, which carries their id and name.
+    #[test]
+    fn test_log_sink() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(NodeLogAction("hello")),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(NodeLogAction("world")),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        let sink = Arc::new(crate::MemoryLogSink::new());
+        graph.set_log_sink(sink.clone());
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert!(NodeLogger::current().is_none());
+        let mut records: Vec<_> = sink
+            .records()
+            .into_iter()
+            .map(|record| (record.node_id, record.node_name, record.message))
+            .collect();
+        records.sort_by_key(|record| record.0);
+        assert_eq!(
+            records,
+            [
+                (node_id, "Node X".to_string(), "hello".to_string()),
+                (node1_id, "Node Y".to_string(), "world".to_string())
+            ]
+        );
+    }
+
+    /// Test for the critical path of a diamond graph.
+    ///
+    /// Node A precedes Node B and Node C, which both precede Node D, and Node D precedes the
+    /// skipped Node E. Node B is the slowest, so the critical path goes through it, both with
+    /// given durations and after a run, where it stops at Node D.
+    #[test]
+    fn test_critical_path() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = [
+            ("Node A", 20),
+            ("Node B", 150),
+            ("Node C", 20),
+            ("Node D", 20),
+        ]
+        .into_iter()
+        .map(|(name, millis)| {
+            let node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(SleepAction(Duration::from_millis(millis))),
+                &mut node_table,
+            );
+            let id = node.id();
+            graph.add_node(Box::new(node));
+            id
+        })
+        .collect();
+        graph.add_edge(ids[0], vec![ids[1], ids[2]]);
+        graph.add_edge(ids[1], vec![ids[3]]);
+        graph.add_edge(ids[2], vec![ids[3]]);
+        let mut node = DefaultNode::new(NodeName::from("Node E"), &mut node_table);
+        node.set_condition(|_, _| false);
+        let skipped_id = node.id();
+        graph.add_node(Box::new(node));
+        graph.add_edge(ids[3], vec![skipped_id]);
+
+        let durations = HashMap::from([
+            (ids[0], Duration::from_secs(1)),
+            (ids[1], Duration::from_secs(1)),
+            (ids[2], Duration::from_secs(3)),
+        ]);
+        assert_eq!(
+            graph.critical_path(&durations).unwrap(),
+            [ids[0], ids[2], ids[3], skipped_id]
+        );
+        assert_eq!(
+            graph.critical_path(&HashMap::new()).unwrap(),
+            [ids[0], ids[1], ids[3], skipped_id]
+        );
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(
+            graph.critical_path_after_run().unwrap(),
+            ["Node A", "Node B", "Node D"]
+        );
+    }
+
+    /// Test for starting the nodes by priority under a limited parallelism.
+    ///
+    /// Node X, Node Y and Node Z are independent, and run one at a time from the highest
+    /// priority to the lowest.
+    #[test]
+    fn test_priority() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        for (label, priority) in [("Node X", 0), ("Node Y", 5), ("Node Z", -1)] {
+            let mut node = DefaultNode::with_action(
+                NodeName::from(label),
+                Box::new(LogAction {
+                    label,
+                    log: log.clone(),
+                    recv: None,
+                }),
+                &mut node_table,
+            );
+            node.set_priority(priority);
+            graph.add_node(Box::new(node));
+        }
+
+        graph.set_max_parallelism(1).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*log.lock().unwrap(), ["Node Y", "Node X", "Node Z"]);
+    }
+
+    /// Test for ordering the execution by priority without a limited parallelism.
+    ///
+    /// Node X, Node Y and Node Z all come after Node W, which goes first even with the lowest
+    /// priority. Node Z has the highest priority and is spawned before its siblings.
+    #[test]
+    fn test_priority_order() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = [("Node W", -5), ("Node X", 0), ("Node Y", 0), ("Node Z", 3)]
+            .into_iter()
+            .map(|(name, priority)| {
+                let mut node = DefaultNode::new(NodeName::from(name), &mut node_table);
+                node.set_priority(priority);
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        graph.add_edge(ids[0], vec![ids[1], ids[2], ids[3]]);
+
+        assert_eq!(
+            graph.execution_order().unwrap(),
+            ["Node W", "Node Z", "Node X", "Node Y"]
+        );
+        assert!(matches!(graph.start(), Ok(true)));
+    }
+
+    /// Test for limiting the rate of the node starts.
+    ///
+    /// Five independent nodes start at most two per 200ms, so the last starts after 400ms.
+    #[test]
+    fn test_start_rate() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        for i in 0..5 {
+            let node = DefaultNode::new(format!("Node {}", i), &mut node_table);
+            graph.add_node(Box::new(node));
+        }
+        assert!(matches!(
+            graph.set_start_rate(0, Duration::from_millis(200)),
+            Err(GraphError::InvalidRate(0))
+        ));
+        graph.set_start_rate(2, Duration::from_millis(200)).unwrap();
+
+        let start = Instant::now();
+        assert!(matches!(graph.start(), Ok(true)));
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(1), "{:?}", elapsed);
+    }
+
+    /// Test for limiting the nodes running at the same time by a resource pool.
+    ///
+    /// Three nodes require one `gpu` each from a pool of 2, while two more nodes require
+    /// nothing. At most two `gpu` nodes run at the same time. A node requiring 3 `gpu` cannot
+    /// run at all.
+    #[test]
+    fn test_resource() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        for i in 0..3 {
+            let mut node = DefaultNode::with_action(
+                format!("GPU {}", i),
+                Box::new(CountAction {
+                    running: running.clone(),
+                    max_running: max_running.clone(),
+                }),
+                &mut node_table,
+            );
+            node.require_resource("gpu", 1);
+            graph.add_node(Box::new(node));
+        }
+        for i in 0..2 {
+            let node = DefaultNode::new(format!("CPU {}", i), &mut node_table);
+            graph.add_node(Box::new(node));
+        }
+        graph.set_resource("gpu", 2);
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+
+        let mut node = DefaultNode::new(NodeName::from("GPU X"), &mut node_table);
+        node.require_resource("gpu", 3);
+        graph.add_node(Box::new(node));
+        graph.reset();
+        assert!(matches!(
+            graph.start(),
+            Err(GraphError::InvalidResource(name, resource)) if name == "GPU X" && resource == "gpu"
+        ));
+    }
+
+    /// Test for retrying failed nodes.
+    ///
+    /// Both nodes fail twice before succeeding. Node X may run 3 times and succeeds at the
+    /// last attempt, Node Y may run only twice and fails.
+    #[test]
+    fn test_node_retry() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 3,
+            }),
+            &mut node_table,
+        );
+        node.set_retry(
+            RetryPolicy::new(3)
+                .with_backoff(RetryBackoff::Fixed(Duration::from_millis(10)))
+                .with_jitter(Duration::from_millis(5)),
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 3,
+            }),
+            &mut node_table,
+        )
+        .with_retry(2, RetryBackoff::None);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let attempts = graph.get_attempts();
+        assert_eq!(attempts[&node_id], 3);
+        assert_eq!(attempts[&node1_id], 2);
+        let results = graph.get_results::<usize>();
+        assert_eq!(*results[&node_id].clone().unwrap(), 3);
+        assert!(results[&node1_id].is_none());
+    }
+
+    /// An implementation of [`Action`] that sums the integers received from the given node
+    /// until its channel is closed, and sends the sum to its successors. Its first run fails
+    /// after sending.
+    pub struct FlakySumAction(NodeId, Arc<AtomicUsize>);
+    #[async_trait]
+    impl Action for FlakySumAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let mut sum = 0;
+            while let Ok(content) = in_channels.recv_from(&self.0).await {
+                sum += *content.get::<i32>().unwrap();
+            }
+            out_channels.send_to_all(Content::new(sum)).await.unwrap();
+            match self.1.fetch_add(1, Ordering::SeqCst) {
+                0 => Output::error("failed at run 1".to_string()),
+                _ => Output::new(sum),
+            }
+        }
+    }
+
+    /// Test for retrying a node between two others.
+    ///
+    /// Node X sends 100 integers to Node Y, which sums them, sends the sum to Node Z and
+    /// fails once. The retry receives the integers again, and Node Z only receives the sum
+    /// of the retry.
+    #[test]
+    fn test_node_retry_channels() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(StreamAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(FlakySumAction(node_id, Arc::new(AtomicUsize::new(0)))),
+            &mut node_table,
+        );
+        node1.set_retry(RetryPolicy::new(2));
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(SumAction(node1_id)),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id]);
+        graph.add_edge(node1_id, vec![node2_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(graph.get_attempts()[&node1_id], 2);
+        assert_eq!(*graph.get_result::<i32>(&node1_id).unwrap(), 5050);
+        assert_eq!(*graph.get_result::<i32>(&node2_id).unwrap(), 5050);
+    }
+
+    /// Test for aggregating the results of several runs.
+    ///
+    /// The same graph runs with the environment variable "factor" set to 1, 2 and 2, and the
+    /// results are collected by a [`ResultAggregator`] labeled with the run.
+    #[test]
+    fn test_aggregate_runs() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(DoubleAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        let mut aggregator = crate::ResultAggregator::new();
+        for (run, factor) in [1usize, 2, 2].into_iter().enumerate() {
+            graph.reset();
+            let mut env = EnvVar::new(NodeTable::new());
+            env.set("factor", factor);
+            graph.set_env(env);
+            graph.start().unwrap();
+            aggregator.add(run, graph.get_outputs());
+        }
+
+        let values = aggregator.values_of::<usize>(&node_id);
+        assert_eq!(values.len(), 3);
+        assert_eq!(*values[&0], 2);
+        assert_eq!(*values[&1], 4);
+        assert!(!aggregator.all_equal::<usize>(&node_id));
+        aggregator.add(0, aggregator.get(&1).unwrap().clone());
+        assert!(aggregator.all_equal::<usize>(&node_id));
+    }
+
+    /// Test for a maximum concurrency of `usize::MAX`, which is the same as no limit.
+    ///
+    /// The lazy Node X still waits for its successor Node Y to start, which is only done
+    /// without limit.
+    #[test]
+    fn test_unlimited_concurrency() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut node_table = NodeTable::new();
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(LogAction {
+                label: "X",
+                log: log.clone(),
+                recv: None,
+            }),
+            &mut node_table,
+        )
+        .lazy();
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(LogAction {
+                label: "Y",
+                log: log.clone(),
+                recv: Some(node_id),
+            }),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+
+        let mut graph = Graph::with_nodes(vec![Box::new(node), Box::new(node1)])
+            .with_max_concurrency(usize::MAX)
+            .unwrap();
+        graph.add_edge(node_id, vec![node1_id]);
+        assert!(graph.max_parallelism.is_none());
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*log.lock().unwrap(), ["Y", "X"]);
+    }
+
+    /// Test for running a chain of nodes with a concurrency of 1.
+    ///
+    /// The nodes start in topological order, and a concurrency of 0 is rejected.
+    #[test]
+    fn test_max_concurrency_order() {
+        assert!(matches!(
+            Graph::new().with_max_concurrency(0),
+            Err(GraphError::InvalidParallelism(0))
+        ));
+
+        let mut graph = Graph::new().with_max_concurrency(1).unwrap();
+        let mut node_table = NodeTable::new();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut recv = None;
+        let mut ids = Vec::new();
+        for label in ["X", "Y", "Z"] {
+            let node = DefaultNode::with_action(
+                format!("Node {}", label),
+                Box::new(LogAction {
+                    label,
+                    log: log.clone(),
+                    recv,
+                }),
+                &mut node_table,
+            );
+            recv = Some(node.id());
+            ids.push(node.id());
+            graph.add_node(Box::new(node));
+        }
+        graph.add_edge(ids[1], vec![ids[2]]);
+        graph.add_edge(ids[0], vec![ids[1]]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*log.lock().unwrap(), vec!["X", "Y", "Z"]);
+    }
+
+    /// Test for a timed out node in a graph with independent branches.
+    ///
+    /// Node X times out and its successor Node Y fails, while the unrelated Node Z still
+    /// runs to completion and stores its output.
+    #[test]
+    fn test_node_timeout_independent_branch() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node_x = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_millis(500))),
+            &mut node_table,
+        )
+        .with_timeout(Duration::from_millis(50));
+        let x_id = node_x.id();
+        let node_y = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(x_id)),
+            &mut node_table,
+        );
+        let y_id = node_y.id();
+        let node_z = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            HelloAction::new(),
+            &mut node_table,
+        )
+        .with_timeout(Duration::from_millis(500));
+        let z_id = node_z.id();
+
+        graph.add_node(Box::new(node_x));
+        graph.add_node(Box::new(node_y));
+        graph.add_node(Box::new(node_z));
+        graph.add_edge(x_id, vec![y_id]);
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let results = graph.get_results::<String>();
+        assert!(results[&x_id].is_none());
+        assert!(results[&y_id].is_none());
+        assert_eq!(*results[&z_id].clone().unwrap(), "Hello world");
+        assert_eq!(graph.get_timed_out(), HashSet::from([x_id]));
+    }
+
+    /// Test for a panicking node with quiet panics.
+    ///
+    /// Node X panics, the panic message is stored as its error, and its successor Node Y
+    /// sees the closed channel instead of waiting forever.
+    #[test]
+    fn test_quiet_panics() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(PanicAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        graph.set_quiet_panics(true);
+        assert!(matches!(graph.start(), Ok(false)));
+        let outputs = graph.get_outputs();
+        assert_eq!(outputs[&node_id].get_err().unwrap(), "panicked: oops");
+        assert!(outputs[&node1_id].is_err());
+    }
+
+    /// Test for building a graph from boxed nodes.
+    #[test]
+    fn test_with_nodes() {
+        let mut node_table = NodeTable::new();
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+
+        let mut graph = Graph::with_nodes(vec![Box::new(node), Box::new(node1)]);
+        graph.add_edge(node_id, vec![node1_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(
+            graph.get_results::<String>()[&node1_id].as_deref().unwrap(),
+            "Hello world"
+        );
+    }
+
+    /// Test for retrying a node with a fixed delay between the attempts.
+    ///
+    /// Node X fails twice before succeeding, the graph completes successfully after 3 attempts.
+    #[test]
+    fn test_node_retry_fixed_delay() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 3,
+            }),
+            &mut node_table,
+        );
+        node.set_retry((3, Duration::from_millis(10)));
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        let start = Instant::now();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(graph.get_attempts()[&node_id], 3);
+    }
+
+    /// Test for the maximum output size.
+    ///
+    /// Node X outputs 8 bytes within its limit, Node Y outputs 16 bytes and fails.
+    #[test]
+    fn test_max_output_size() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(RepeatAction(8)),
+            &mut node_table,
+        );
+        node.set_max_output_size(8);
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RepeatAction(16)),
+            &mut node_table,
+        );
+        node1.set_max_output_size(8);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let outputs = graph.get_outputs();
+        assert_eq!(outputs[&node_id].get_out().unwrap().size(), Some(8));
+        assert_eq!(
+            outputs[&node1_id].get_err().unwrap(),
+            GraphError::OutputTooLarge("Node Y".to_string(), 16).to_string()
+        );
+    }
+
+    /// Test for the maximum output size of the contents sent to the successors.
+    ///
+    /// Node X sends 16 bytes over its limit of 8, so the send fails and Node Y receives
+    /// nothing.
+    #[test]
+    fn test_max_output_size_sent() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SendRepeatAction(16)),
+            &mut node_table,
+        );
+        node.set_max_output_size(8);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAllAction(vec![node_id])),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        assert!(matches!(graph.start(), Ok(false)));
+        assert_eq!(graph.get_errors()[&node_id], "TooLarge(16)");
+        assert_eq!(*graph.get_result::<i32>(&node1_id).unwrap(), 0);
+    }
+
+    /// Test for polling the progress while the graph is running.
+    ///
+    /// Node X finishes at once while Node Y sleeps for 300ms, so the graph is half done
+    /// when polled after 100ms.
+    #[test]
+    fn test_progress() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(SleepAction(Duration::from_millis(300))),
+            &mut node_table,
+        );
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        assert_eq!(graph.progress(), 0.0);
+
+        let progress = graph.progress_handle();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let poll = tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                progress.fraction()
+            });
+            assert!(matches!(graph.start_async().await, Ok(true)));
+            assert_eq!(poll.await.unwrap(), 0.5);
+        });
+        assert_eq!(graph.progress(), 1.0);
+    }
+
+    /// Test for polling the states of the nodes while the graph is running.
+    ///
+    /// Node X sleeps for 300ms, and Node Y waits for it through a conditional edge, so they
+    /// are running and waiting when polled after 100ms. Node Y is skipped in the end, since
+    /// Node X has no output.
+    #[test]
+    fn test_node_states() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_millis(300))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_conditional_edge(node_id, node1_id, |_: &String| true);
+        assert!(graph.node_states().is_empty());
+
+        let states = graph.node_states_handle();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let poll = tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                states.snapshot()
+            });
+            assert!(matches!(graph.start_async().await, Ok(true)));
+            let states = poll.await.unwrap();
+            assert_eq!(states[&node_id], NodeState::Running);
+            assert_eq!(states[&node1_id], NodeState::WaitingOnDeps);
+        });
+        let states = graph.node_states();
+        assert_eq!(states[&node_id], NodeState::Succeeded);
+        assert_eq!(states[&node1_id], NodeState::Skipped);
+        assert!(states.values().all(NodeState::is_terminal));
+    }
+
+    /// Test for the execution order of a diamond graph.
+    ///
+    /// Node A precedes Node B and Node C, which both precede Node D. The graph still runs
+    /// after its order is computed.
+    #[test]
+    fn test_execution_order() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = ["Node A", "Node B", "Node C", "Node D"]
+            .into_iter()
+            .map(|name| {
+                let node = DefaultNode::new(NodeName::from(name), &mut node_table);
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        graph.add_edge(ids[0], vec![ids[1], ids[2]]);
+        graph.add_edge(ids[1], vec![ids[3]]);
+        graph.add_edge(ids[2], vec![ids[3]]);
+
+        let order = graph.execution_order().unwrap();
+        assert_eq!(order.len(), 4);
+        assert_eq!(order[0], "Node A");
+        assert_eq!(order[3], "Node D");
+        assert!(matches!(graph.start(), Ok(true)));
+    }
+
+    /// Test for the execution order of the same graph built twice.
+    ///
+    /// Node B, Node C and Node D are all ready after Node A, so they are ordered by id.
+    #[test]
+    fn test_deterministic_execution_order() {
+        fn build() -> Graph {
+            let mut graph = Graph::new();
+            let mut node_table = NodeTable::new();
+            let ids: Vec<NodeId> = ["Node A", "Node B", "Node C", "Node D", "Node E"]
+                .into_iter()
+                .map(|name| {
+                    let node = DefaultNode::new(NodeName::from(name), &mut node_table);
+                    let id = node.id();
+                    graph.add_node(Box::new(node));
+                    id
+                })
+                .collect();
+            graph.add_edge(ids[0], vec![ids[3], ids[1], ids[2]]);
+            graph.add_edge(ids[1], vec![ids[4]]);
+            graph
+        }
+
+        let order = build().execution_order().unwrap();
+        assert_eq!(order, ["Node A", "Node B", "Node C", "Node D", "Node E"]);
+        for _ in 0..10 {
+            assert_eq!(build().execution_order().unwrap(), order);
+        }
+    }
+
+    /// Test for a failed node in a graph with a sibling branch.
+    ///
+    /// Node A fails and its successor Node B fails too, while the sibling branch of Node C
+    /// and Node D, which runs after the failure, still completes.
+    #[test]
+    fn test_failure_sibling_branch() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node_a = DefaultNode::with_action(
+            NodeName::from("Node A"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: usize::MAX,
+            }),
+            &mut node_table,
+        );
+        let a_id = node_a.id();
+        let node_b = DefaultNode::with_action(
+            NodeName::from("Node B"),
+            Box::new(RecvAction(a_id)),
+            &mut node_table,
+        );
+        let b_id = node_b.id();
+        let node_c = DefaultNode::with_action(
+            NodeName::from("Node C"),
+            Box::new(SleepAction(Duration::from_millis(100))),
+            &mut node_table,
+        );
+        let c_id = node_c.id();
+        let node_d = DefaultNode::with_action(
+            NodeName::from("Node D"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let d_id = node_d.id();
+
+        graph.add_node(Box::new(node_a));
+        graph.add_node(Box::new(node_b));
+        graph.add_node(Box::new(node_c));
+        graph.add_node(Box::new(node_d));
+        graph.add_edge(a_id, vec![b_id]);
+        graph.add_edge(c_id, vec![d_id]);
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let outputs = graph.get_outputs();
+        assert!(outputs[&a_id].is_err());
+        assert!(outputs[&b_id].is_err());
+        assert!(!outputs[&c_id].is_err());
+        assert_eq!(
+            *graph.get_results::<String>()[&d_id].clone().unwrap(),
+            "Hello world"
+        );
+    }
+
+    /// Test for running a graph twice.
+    ///
+    /// Node X sends to Node Y through a channel, which is closed after the first run and
+    /// rebuilt by the reset, so both runs deliver fresh results.
+    #[test]
+    fn test_reset_rerun() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SendAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        for _ in 0..2 {
+            assert!(matches!(graph.start(), Ok(true)));
+            assert_eq!(
+                *graph.get_results::<String>()[&node1_id].clone().unwrap(),
+                "Hello world"
+            );
+            graph.reset();
+            assert!(graph.get_outputs().is_empty());
+        }
+    }
+
+    /// Test for exporting a fan-in graph in the DOT format.
+    ///
+    /// Node A, Node B and the quoted Node "C" all precede Node D.
+    #[test]
+    fn test_to_dot() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = ["Node A", "Node B", "Node \"C\"", "Node D"]
+            .into_iter()
+            .map(|name| {
+                let node = DefaultNode::new(NodeName::from(name), &mut node_table);
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        for id in &ids[..3] {
+            graph.add_edge(*id, vec![ids[3]]);
+        }
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains(&format!("{} [label=\"Node \\\"C\\\"\"];", ids[2].0)));
+        let edge = regex::Regex::new(r"(?m)^\s*(\d+) -> (\d+);$").unwrap();
+        let edges: HashSet<(usize, usize)> = edge
+            .captures_iter(&dot)
+            .map(|c| (c[1].parse().unwrap(), c[2].parse().unwrap()))
+            .collect();
+        let expected: HashSet<(usize, usize)> =
+            ids[..3].iter().map(|id| (id.0, ids[3].0)).collect();
+        assert_eq!(edges, expected);
+
+        graph.init();
+        let dot = graph.to_dot();
+        assert!(dot.contains(&format!("{} [label=\"Node D\", xlabel=\"3\"];", ids[3].0)));
+    }
+
+    /// Test for importing a graph in the DOT format, with the actions given by name.
+    ///
+    /// Node A precedes Node B and Node C, and the graph exported again has the same edges
+    /// between the same names.
+    #[test]
+    fn test_from_dot() {
+        let src = r#"
+            digraph {
+                a [label="Node A"];
+                a -> b -> c; // b has no label.
+                a -> c [color=red];
+            }
+        "#;
+        let mock = crate::testing::MockAction::returning(1);
+        let mut actions: HashMap<String, Box<dyn Action>> = HashMap::new();
+        actions.insert("Node A".to_string(), Box::new(mock.clone()));
+        let mut graph = Graph::from_dot(src, actions).unwrap();
+
+        let names: HashMap<NodeId, String> = graph
+            .nodes
+            .iter()
+            .map(|(id, node)| (*id, node.name().to_string()))
+            .collect();
+        assert_eq!(graph.execution_order().unwrap(), ["Node A", "b", "c"]);
+        assert!(graph.start().unwrap());
+        assert_eq!(mock.call_count(), 1);
+
+        let edge = regex::Regex::new(r"(?m)^\s*(\d+) -> (\d+);$").unwrap();
+        let edges: HashSet<(String, String)> = edge
+            .captures_iter(&graph.to_dot())
+            .map(|c| {
+                let name = |i: usize| names[&NodeId(c[i].parse().unwrap())].clone();
+                (name(1), name(2))
+            })
+            .collect();
+        let expected: HashSet<(String, String)> = [("Node A", "b"), ("b", "c"), ("Node A", "c")]
+            .into_iter()
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        assert_eq!(edges, expected);
+
+        assert!(matches!(
+            Graph::from_dot("digraph { a -> }", HashMap::new()),
+            Err(GraphError::InvalidDot(_))
+        ));
+    }
+
+    /// Test for skipping a node with a cached output.
+    ///
+    /// Node X runs once in the first run and caches its output. In the second run Node X is
+    /// skipped, and Node Y still receives the cached output.
+    #[test]
+    fn test_cached_output() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node.set_cache_key("hello");
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        graph.set_cache(Arc::new(crate::MemoryCache::new()));
+
+        for _ in 0..2 {
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+            let results = graph.get_results::<String>();
+            assert_eq!(*results[&node_id].clone().unwrap(), "Hello world");
+            assert_eq!(*results[&node1_id].clone().unwrap(), "Hello world");
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    /// Test for caching an output under a key hashed from the environment.
+    ///
+    /// Node X runs once across two runs with the same input, and again once the input
+    /// changes.
+    #[test]
+    fn test_cached_output_by_env() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node.set_cache_key_with(|env, _| env.get::<u64>("input").unwrap_or_default());
+        graph.add_node(Box::new(node));
+        graph.set_cache(Arc::new(crate::MemoryCache::new()));
+
+        for input in [1u64, 1, 2] {
+            let mut env = EnvVar::new(NodeTable::default());
+            env.set("input", input);
+            graph.set_env(env);
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    /// Test for caching an output under a key hashed from the outputs of the predecessors.
+    ///
+    /// Node X doubles the variable "factor", and Node Y runs once across two runs with the
+    /// same output of Node X, and again once it changes.
+    #[test]
+    fn test_cached_output_by_inputs() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(DoubleAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node1.set_cache_key_with(move |_, inputs| {
+            let content = inputs[&node_id].get_out().unwrap();
+            *content.get::<usize>().unwrap() as u64
+        });
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        graph.set_cache(Arc::new(crate::MemoryCache::new()));
+
+        for factor in [1usize, 1, 2] {
+            let mut env = EnvVar::new(NodeTable::default());
+            env.set("factor", factor);
+            graph.set_env(env);
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    /// Test for limiting the total duration of the retries.
+    ///
+    /// Node X always fails and may run 100 times, 20ms apart, but its retries are limited
+    /// to 100ms in total.
+    #[test]
+    fn test_retry_max_duration() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: usize::MAX,
+            }),
+            &mut node_table,
+        );
+        node.set_retry(
+            RetryPolicy::from((100, Duration::from_millis(20)))
+                .with_max_duration(Duration::from_millis(100)),
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        let start = Instant::now();
+        assert!(matches!(graph.start(), Ok(false)));
+        assert!(start.elapsed() < Duration::from_millis(500));
+        let attempts = graph.get_attempts()[&node_id];
+        assert!((2..=6).contains(&attempts));
+    }
+
+    /// Test for reporting the nodes of a loop.
+    ///
+    /// Node X is its own successor, and Node Y and Node Z depend on each other.
+    #[test]
+    fn test_loop_nodes() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+        graph.add_edge(node_id, vec![node_id]);
+        match graph.start() {
+            Err(GraphError::GraphLoopDetected(names)) => assert_eq!(names, ["Node X"]),
+            _ => panic!("the loop is not detected"),
+        }
+
+        let mut graph = Graph::new();
+        let node1 = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        let node1_id = node1.id();
+        let node2 = DefaultNode::new(NodeName::from("Node Z"), &mut node_table);
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node1_id, vec![node2_id]);
+        graph.add_edge(node2_id, vec![node1_id]);
+        assert_eq!(graph.find_cycle(), Some(vec![node1_id, node2_id]));
+    }
+
+    /// Test for resuming a run from a checkpoint.
+    ///
+    /// In the first run Node A and Node B complete while Node C fails. The second run builds
+    /// the nodes again and resumes from the checkpoint, so only Node C runs, and it receives
+    /// the output of Node B restored from the checkpoint.
+    #[test]
+    fn test_checkpoint_resume() {
+        let path = std::env::temp_dir().join(format!("dagrs-checkpoint-{}", std::process::id()));
+        let runs = Arc::new(AtomicUsize::new(0));
+        let build = |fail: bool| {
+            let mut node_table = NodeTable::new();
+            let node_a = DefaultNode::with_action(
+                NodeName::from("Node A"),
+                Box::new(CountedHelloAction(runs.clone())),
+                &mut node_table,
+            );
+            let node_b = DefaultNode::with_action(
+                NodeName::from("Node B"),
+                Box::new(CountedHelloAction(runs.clone())),
+                &mut node_table,
+            );
+            let action: Box<dyn Action> = if fail {
+                Box::new(FlakyAction {
+                    runs: Arc::new(AtomicUsize::new(0)),
+                    succeed_at: usize::MAX,
+                })
+            } else {
+                Box::new(RecvAction(node_b.id()))
+            };
+            let node_c =
+                DefaultNode::with_action(NodeName::from("Node C"), action, &mut node_table);
+            let ids = [node_a.id(), node_b.id(), node_c.id()];
+            let mut graph =
+                Graph::with_nodes(vec![Box::new(node_a), Box::new(node_b), Box::new(node_c)]);
+            graph.add_edge(ids[0], vec![ids[1]]);
+            graph.add_edge(ids[1], vec![ids[2]]);
+            (graph, ids)
+        };
+
+        let (mut graph, _) = build(true);
+        assert!(matches!(graph.start(), Ok(false)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        graph.checkpoint_to(&path).unwrap();
+
+        let (mut graph, ids) = build(false);
+        graph.resume_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *graph.get_results::<String>()[&ids[2]].clone().unwrap(),
+            "Hello world"
+        );
+    }
+
+    /// Test for checkpointing nodes with the same name.
+    ///
+    /// Two nodes are named Node A, so the graph cannot be saved to a checkpoint, and cannot
+    /// resume from one either.
+    #[test]
+    fn test_checkpoint_duplicate_names() {
+        let path =
+            std::env::temp_dir().join(format!("dagrs-checkpoint-duplicate-{}", std::process::id()));
+        let build = |names: &[&str]| {
+            let mut node_table = NodeTable::new();
+            let nodes: Vec<Box<dyn Node>> = names
+                .iter()
+                .map(|&name| {
+                    Box::new(DefaultNode::with_action(
+                        NodeName::from(name),
+                        HelloAction::new(),
+                        &mut node_table,
+                    )) as Box<dyn Node>
+                })
+                .collect();
+            Graph::with_nodes(nodes)
+        };
+
+        let mut graph = build(&["Node A", "Node A"]);
+        assert!(matches!(graph.start(), Ok(true)));
+        let e = graph.checkpoint_to(&path).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+
+        let mut graph = build(&["Node A"]);
+        assert!(matches!(graph.start(), Ok(true)));
+        graph.checkpoint_to(&path).unwrap();
+        let mut graph = build(&["Node A", "Node A"]);
+        graph.resume_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            graph.start(),
+            Err(GraphError::DuplicateName(name)) if name == "Node A"
+        ));
+    }
+
+    /// Test for a dry run.
+    ///
+    /// A chain of Node X and Node Y is validated and ordered without running, and an edge to
+    /// a node not in the graph is rejected.
+    #[test]
+    fn test_dry_run() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        let node1_id = node1.id();
+        let unknown_id = node_table.alloc_id_for("Node Z");
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        let plan = graph.dry_run().unwrap();
+        assert_eq!(plan.len(), 2);
+        assert_eq!((plan[0].id, plan[0].name.as_str()), (node_id, "Node X"));
+        assert!(plan[0].predecessors.is_empty());
+        assert_eq!((plan[1].id, plan[1].name.as_str()), (node1_id, "Node Y"));
+        assert_eq!(plan[1].predecessors, ["Node X"]);
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        graph.add_edge(node1_id, vec![unknown_id]);
+        assert!(matches!(graph.dry_run(), Err(GraphError::UnknownNode(id)) if id == unknown_id));
+        assert!(matches!(graph.start(), Err(GraphError::UnknownNode(_))));
+    }
+
+    /// Test for a dry run checking the environment variables the nodes require.
+    ///
+    /// Node X requires "factor" and Node Y overrides it, so the dry run fails until the
+    /// graph sets "factor", and a loop between them is detected after that. No node runs.
+    #[test]
+    fn test_dry_run_env() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node.require_env("factor");
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node1.require_env("factor");
+        node1.set_env_override("factor", 2usize);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        assert!(matches!(
+            graph.dry_run(),
+            Err(GraphError::MissingEnv(name, var)) if name == "Node X" && var == "factor"
+        ));
+        assert!(matches!(graph.start(), Err(GraphError::MissingEnv(..))));
+
+        let mut env = EnvVar::new(node_table);
+        env.set("factor", 1usize);
+        graph.set_env(env);
+        assert_eq!(graph.dry_run().unwrap().len(), 2);
+
+        graph.add_edge(node1_id, vec![node_id]);
+        assert!(matches!(
+            graph.dry_run(),
+            Err(GraphError::GraphLoopDetected(_))
+        ));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    /// Test for running the nodes in a given order.
+    ///
+    /// Node X precedes Node Y, so the order [Node Y, Node X] is rejected, while an order
+    /// with all the nodes after their predecessors is used as is.
+    #[test]
+    fn test_set_execution_order() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = ["Node X", "Node Y", "Node Z"]
+            .into_iter()
+            .map(|name| {
+                let node = DefaultNode::new(NodeName::from(name), &mut node_table);
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        graph.add_edge(ids[0], vec![ids[1]]);
+
+        assert!(matches!(
+            graph.set_execution_order(vec![ids[1], ids[0], ids[2]]),
+            Err(GraphError::InvalidOrder(_))
+        ));
+        assert!(matches!(
+            graph.set_execution_order(vec![ids[0], ids[1]]),
+            Err(GraphError::InvalidOrder(_))
+        ));
+        graph
+            .set_execution_order(vec![ids[2], ids[0], ids[1]])
+            .unwrap();
+        assert_eq!(
+            graph.execution_order().unwrap(),
+            ["Node Z", "Node X", "Node Y"]
+        );
+        assert!(matches!(graph.start(), Ok(true)));
+    }
+
+    /// Test for transforming the outputs of the nodes.
+    ///
+    /// Node X turns its output into upper case, and Node Y gives its error a prefix.
+    #[test]
+    fn test_post_process() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        node.set_post_process(|out| match out.get_out() {
+            Some(content) => Output::new(content.get::<String>().unwrap().to_uppercase()),
+            None => out,
+        });
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: usize::MAX,
+            }),
+            &mut node_table,
+        );
+        node1.set_post_process(|out| match out.get_err() {
+            Some(e) => Output::error(format!("[Node Y] {}", e)),
+            None => out,
+        });
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let outputs = graph.get_outputs();
+        assert_eq!(
+            outputs[&node_id]
+                .get_out()
+                .unwrap()
+                .get::<String>()
+                .unwrap(),
+            "HELLO WORLD"
+        );
+        assert_eq!(
+            outputs[&node1_id].get_err().unwrap(),
+            "[Node Y] failed at run 1"
+        );
+    }
+
+    /// An implementation of [`GraphObserver`] that records the events.
+    #[derive(Default)]
+    struct LogObserver(std::sync::Mutex<Vec<String>>);
+    impl GraphObserver for LogObserver {
+        fn on_node_scheduled(&self, _: NodeId, name: &str) {
+            self.0.lock().unwrap().push(format!("scheduled {}", name));
+        }
+        fn on_node_skipped(&self, _: NodeId, name: &str) {
+            self.0.lock().unwrap().push(format!("skipped {}", name));
+        }
+        fn on_node_started(&self, _: NodeId, name: &str) {
+            self.0.lock().unwrap().push(format!("started {}", name));
+        }
+        fn on_node_succeeded(&self, _: NodeId, name: &str, _: Duration) {
+            self.0.lock().unwrap().push(format!("succeeded {}", name));
+        }
+        fn on_node_failed(&self, _: NodeId, name: &str, err: &str, _: Duration) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("failed {}: {}", name, err));
+        }
+        fn on_graph_completed(&self, success: bool) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("completed {}", success));
+        }
+    }
+
+    /// Test for observing the lifecycle events.
+    ///
+    /// Node X succeeds, then its successor Node Y fails.
+    #[test]
+    fn test_observer() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        let observer = Arc::new(LogObserver::default());
+        graph.set_observer(observer.clone());
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let events = observer.0.lock().unwrap();
+        assert_eq!(events.len(), 7);
+        assert_eq!(events[6], "completed false");
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("scheduled Node X") < position("scheduled Node Y"));
+        assert!(position("started Node X") < position("succeeded Node X"));
+        assert!(position("started Node Y") < position("failed Node Y: Closed"));
+    }
+
+    /// An implementation of [`GraphObserver`] that records the durations of the nodes.
+    #[derive(Default)]
+    struct DurationObserver(std::sync::Mutex<HashMap<NodeId, Duration>>);
+    impl GraphObserver for DurationObserver {
+        fn on_node_succeeded(&self, id: NodeId, _: &str, duration: Duration) {
+            self.0.lock().unwrap().insert(id, duration);
+        }
+        fn on_node_failed(&self, id: NodeId, _: &str, _: &str, duration: Duration) {
+            self.0.lock().unwrap().insert(id, duration);
+        }
+    }
+
+    /// Test for observing the durations of the nodes.
+    ///
+    /// Node X sleeps for 100ms, and Node Y fails at once. Each reported duration is the
+    /// measured one.
+    #[test]
+    fn test_observer_durations() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_millis(100))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 2,
+            }),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        let observer = Arc::new(DurationObserver::default());
+        graph.set_observer(observer.clone());
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let durations = observer.0.lock().unwrap();
+        assert!(durations[&node_id] >= Duration::from_millis(100));
+        assert!(durations[&node1_id] < Duration::from_millis(100));
+        assert_eq!(*durations, graph.get_durations());
+    }
+
+    /// An observer that panics on every event.
+    struct PanicObserver;
+    impl GraphObserver for PanicObserver {
+        fn on_node_started(&self, _: NodeId, _: &str) {
+            panic!("observer panicked")
+        }
+    }
+
+    /// Test for several observers.
+    ///
+    /// Node Y waits for Node X, and Node Z waits for Node Y, so the events follow the
+    /// dependency order. Node W waits for Node X too, and is skipped. The panics of the first
+    /// observer do not stop the second one.
+    #[test]
+    fn test_observers() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut ids = Vec::new();
+        for name in ["Node X", "Node Y", "Node Z", "Node W"] {
+            let node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(CountedHelloAction(Arc::new(AtomicUsize::new(0)))),
+                &mut node_table,
+            );
+            ids.push(node.id());
+            graph.add_node(Box::new(node));
+        }
+        graph.add_conditional_edge(ids[0], ids[1], |_: &String| true);
+        graph.add_conditional_edge(ids[1], ids[2], |_: &String| true);
+        graph.add_conditional_edge(ids[0], ids[3], |_: &String| false);
+        let observer = Arc::new(LogObserver::default());
+        graph.add_observer(Arc::new(PanicObserver));
+        graph.add_observer(observer.clone());
+        graph.set_quiet_panics(true);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        let events = observer.0.lock().unwrap();
+        assert_eq!(events.len(), 14);
+        assert_eq!(events[13], "completed true");
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("scheduled Node X") < position("scheduled Node Y"));
+        assert!(position("scheduled Node Y") < position("scheduled Node Z"));
+        assert!(position("succeeded Node X") < position("started Node Y"));
+        assert!(position("succeeded Node Y") < position("started Node Z"));
+        assert!(position("skipped Node W") < position("succeeded Node W"));
+    }
+
+    /// Test for getting the errors of the failed nodes.
+    ///
+    /// Node X succeeds without sending anything, so its successor Node Y fails.
+    #[test]
+    fn test_get_errors() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        assert!(matches!(graph.start(), Ok(false)));
+        assert_eq!(
+            graph.get_errors(),
+            HashMap::from([(node1_id, "Closed".to_string())])
+        );
+    }
+
+    /// Test for building a graph incrementally.
+    ///
+    /// Node X and Node Y run, then Node Z is added to the graph, which runs again.
+    #[test]
+    fn test_add_node_after_start() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(Arc::new(AtomicUsize::new(0)))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        assert!(matches!(graph.start(), Ok(true)));
+
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node2_id]);
+        assert!(matches!(graph.start(), Ok(true)));
+        let result = graph.get_result::<String>(&node2_id).unwrap();
+        assert_eq!(*result, "Hello world");
+    }
+
+    /// Test for adding and removing nodes after aborting a graph that has not started.
+    ///
+    /// The abort is kept, so the graph does not run and Node X never runs.
+    #[test]
+    fn test_add_node_after_abort() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let node = DefaultNode::new(NodeName::from("Node W"), &mut node_table);
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+        graph.abort_handle().abort();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        graph.add_node(Box::new(node1));
+        assert!(graph.remove_node(&node_id).is_some());
+
+        assert!(matches!(graph.start(), Ok(false)));
+        assert!(graph.abort_handle().is_aborted());
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    /// Test for aborting a running graph.
+    ///
+    /// Node X sleeps for 5s, and the graph is aborted from another thread after 100ms. The
+    /// run completes quickly, and Node Y never receives anything from Node X.
+    #[test]
+    fn test_abort() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_secs(5))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        let handle = graph.abort_handle();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            handle.abort();
+        });
+        let start = Instant::now();
+        assert!(matches!(graph.start(), Ok(false)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        let errors = graph.get_errors();
+        assert_eq!(errors[&node_id], "aborted");
+        assert_eq!(errors[&node1_id], "aborted");
+        let report = graph.execution_report();
+        assert_eq!(report.get(&node1_id).unwrap().status, NodeStatus::Cancelled);
+
+        graph.reset();
+        assert!(!graph.abort_handle().is_aborted());
+    }
+
+    /// An implementation of [`Action`] that aborts the graph, and returns at once.
+    pub struct AbortAction(GraphAbortHandle, bool);
+    #[async_trait]
+    impl Action for AbortAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            self.0.abort();
+            match self.1 {
+                true => Output::error("Closed".to_string()),
+                false => Output::new(1usize),
+            }
+        }
+    }
+
+    /// Test for reporting a node completing while the graph is aborted.
+    ///
+    /// Node X aborts the graph and succeeds, so its output is kept. Node Y aborts the graph
+    /// and fails, so it is reported as aborted. Each result is reported once to the observer.
+    #[test]
+    fn test_abort_reported_once() {
+        for (name, fail) in [("Node X", false), ("Node Y", true)] {
+            let mut graph = Graph::new();
+            let mut node_table = NodeTable::new();
+
+            let node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(AbortAction(graph.abort_handle(), fail)),
+                &mut node_table,
+            );
+            let node_id = node.id();
+            graph.add_node(Box::new(node));
+            let observer = Arc::new(LogObserver::default());
+            graph.set_observer(observer.clone());
+
+            assert!(matches!(graph.start(), Ok(false)));
+            let result = match fail {
+                true => {
+                    assert_eq!(graph.get_errors()[&node_id], "aborted");
+                    format!("failed {}: aborted", name)
+                }
+                false => {
+                    assert_eq!(graph.get_results::<usize>()[&node_id].as_deref(), Some(&1));
+                    format!("succeeded {}", name)
+                }
+            };
+            let events = observer.0.lock().unwrap();
+            assert_eq!(
+                *events,
+                [
+                    format!("scheduled {}", name),
+                    format!("started {}", name),
+                    result,
+                    "completed false".to_string()
+                ]
+            );
+        }
+    }
+
+    /// An implementation of [`Action`] that raises SIGINT in this process, then sleeps for
+    /// the given duration.
+    #[cfg(all(unix, feature = "signal"))]
+    pub struct RaiseSleepAction(Duration);
+    #[cfg(all(unix, feature = "signal"))]
+    #[async_trait]
+    impl Action for RaiseSleepAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            // SAFETY: `raise` has no memory safety requirements.
+            unsafe { libc::raise(libc::SIGINT) };
+            tokio::time::sleep(self.0).await;
+            Output::empty()
+        }
+    }
+
+    /// Get the handler of SIGINT currently set in this process.
+    #[cfg(all(unix, feature = "signal"))]
+    fn sigint_handler() -> libc::sighandler_t {
+        // SAFETY: `sigaction` is a plain C struct, for which all zero bytes are a valid value.
+        let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+        // SAFETY: `action` is valid for writes during the call, and a null new action only
+        // queries the disposition.
+        let queried = unsafe { libc::sigaction(libc::SIGINT, std::ptr::null(), &mut action) };
+        assert_eq!(queried, 0);
+        action.sa_sigaction
+    }
+
+    /// Test for aborting a graph on SIGINT.
+    ///
+    /// Node X completes at once, while Node Y raises SIGINT, then sleeps for 5s. The signal
+    /// cancels Node Y and the result of Node X is kept, in two runs. Once each run ends, SIGINT
+    /// has the handler it had before the run again.
+    #[cfg(all(unix, feature = "signal"))]
+    #[test]
+    fn test_handle_signals() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RaiseSleepAction(Duration::from_secs(5))),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_conditional_edge(node_id, node1_id, |_: &String| true);
+        graph.handle_signals(true);
+
+        let handler = sigint_handler();
+        for _ in 0..2 {
+            let start = Instant::now();
+            assert!(matches!(graph.start(), Ok(false)));
+            assert!(start.elapsed() < Duration::from_secs(1));
+            assert_eq!(
+                *graph.get_result::<String>(&node_id).unwrap(),
+                "Hello world"
+            );
+            assert_eq!(graph.get_errors()[&node1_id], "aborted");
+            assert_eq!(sigint_handler(), handler);
+            graph.reset();
+        }
+    }
+
+    /// Test for aborting a graph running past its deadline.
+    ///
+    /// Node X sleeps for 5s, but the graph has a deadline of 100ms, so the run returns
+    /// promptly and Node X is cancelled. Once reset, the deadline is no longer exceeded.
+    #[test]
+    fn test_deadline() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_secs(5))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+        graph.set_deadline(Duration::from_millis(100));
+
+        let start = Instant::now();
+        assert!(matches!(graph.start(), Ok(false)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(graph.deadline_exceeded());
+        let report = graph.execution_report();
+        assert!(report.deadline_exceeded);
+        assert_eq!(report.get(&node_id).unwrap().status, NodeStatus::Cancelled);
+
+        graph.reset();
+        assert!(!graph.deadline_exceeded());
+    }
+
+    /// Test for forwarding only a sample of the node events to an observer.
+    ///
+    /// Node X and four other nodes succeed, and Node Y fails. Only one in four of the 17
+    /// scheduled, started and succeeded events is forwarded, but the failure and the
+    /// completion are.
+    #[test]
+    fn test_sampled_observer() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        for i in 0..4 {
+            let node = DefaultNode::new(format!("Node {}", i), &mut node_table);
+            graph.add_node(Box::new(node));
+        }
+        let observer = Arc::new(LogObserver::default());
+        graph.set_observer(Arc::new(SampledObserver::new(observer.clone(), 4)));
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let events = observer.0.lock().unwrap();
+        assert_eq!(events.len(), 7);
+        assert!(events.contains(&"failed Node Y: Closed".to_string()));
+        assert_eq!(events[6], "completed false");
+    }
+
+    /// Test for skipping a node by its condition.
+    ///
+    /// Node X sends to Node Y and Node Z, and Node Y is connected to Node Z. Node Y is skipped,
+    /// so Node Z only receives the content of Node X, and still succeeds.
+    #[test]
+    fn test_condition() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SendAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(SendAction),
+            &mut node_table,
+        );
+        node1.set_condition(|_, _| false);
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(RecvAllAction(vec![node_id, node1_id])),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id, node2_id]);
+        graph.add_edge(node1_id, vec![node2_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(graph.get_skipped(), HashSet::from([node1_id]));
+        assert_eq!(*graph.get_result::<i32>(&node2_id).unwrap(), 1);
+        assert!(graph.get_outputs()[&node1_id].get_out().is_none());
+    }
+
+    /// Test for skipping a node by a condition on the outputs of its predecessors.
+    ///
+    /// Node X doubles the variable "factor", and Node Y only runs when the output of Node X
+    /// is 4, so it is skipped with a factor of 1 and runs with a factor of 2.
+    #[test]
+    fn test_condition_on_inputs() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(DoubleAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        node1.set_condition(move |_, inputs| {
+            let content = inputs[&node_id].get_out().unwrap();
+            *content.get::<usize>().unwrap() == 4
+        });
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+
+        for (factor, skipped) in [(1usize, true), (2, false)] {
+            let mut env = EnvVar::new(NodeTable::default());
+            env.set("factor", factor);
+            graph.set_env(env);
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+            assert_eq!(graph.get_skipped().contains(&node1_id), skipped);
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    /// Test for overriding an environment variable for a single node.
+    ///
+    /// Node X and Node Y both double the variable "factor", which Node Y overrides.
+    #[test]
+    fn test_env_override() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(DoubleAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let mut node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(DoubleAction),
+            &mut node_table,
+        );
+        node1.set_env_override("factor", 5usize);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        let mut env = EnvVar::new(node_table);
+        env.set("factor", 1usize);
+        graph.set_env(env);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<usize>(&node_id).unwrap(), 2);
+        assert_eq!(*graph.get_result::<usize>(&node1_id).unwrap(), 10);
+    }
+
+    /// Test for the environments of the nodes, layered over the one of the graph.
+    ///
+    /// Node X and Node Y set "factor" to 3 and 4 in their own environments, and both read
+    /// "scale" from the graph, so they output 30 and 40. Node Z reads both from the graph.
+    #[test]
+    fn test_node_env() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let ids: Vec<NodeId> = [
+            ("Node X", Some(3usize)),
+            ("Node Y", Some(4)),
+            ("Node Z", None),
+        ]
+        .into_iter()
+        .map(|(name, factor)| {
+            let mut node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(ScaleAction),
+                &mut node_table,
+            );
+            if let Some(factor) = factor {
+                let mut env = EnvVar::new(NodeTable::default());
+                env.set("factor", factor);
+                node.set_env(env);
+            }
+            let id = node.id();
+            graph.add_node(Box::new(node));
+            id
+        })
+        .collect();
+        let mut env = EnvVar::new(node_table);
+        env.set("factor", 1usize);
+        env.set("scale", 10usize);
+        graph.set_env(env);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        let results: Vec<usize> = ids
+            .iter()
+            .map(|id| *graph.get_result::<usize>(id).unwrap())
+            .collect();
+        assert_eq!(results, [30, 40, 10]);
+    }
+
+    /// Test for the OS environment variables that are not valid Unicode, which are skipped
+    /// instead of making the merge panic.
+    #[cfg(unix)]
+    #[test]
+    fn test_non_unicode_os_env() {
+        use std::os::unix::ffi::OsStrExt;
+
+        std::env::set_var("DAGRS_UNICODE_KEY", "ok");
+        std::env::set_var(
+            "DAGRS_UNICODE_RAW",
+            std::ffi::OsStr::from_bytes(b"\xff\xfe"),
+        );
+        let env = EnvVar::from_env_prefix("DAGRS_UNICODE_");
+        assert_eq!(env.get::<String>("key").unwrap(), "ok");
+        assert!(env.get::<String>("raw").is_none());
+    }
+
+    /// Test for an output carrying several named contents.
+    ///
+    /// Node X outputs a count and a label, Node Y reads the count and Node Z the label.
+    #[test]
+    fn test_named_outputs() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountAndLabelAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(NamedAction::<usize>("count", Default::default())),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(NamedAction::<String>("label", Default::default())),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id, node2_id]);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<usize>(&node1_id).unwrap(), 3);
+        assert_eq!(*graph.get_result::<String>(&node2_id).unwrap(), "ok");
+        let outputs = graph.get_outputs();
+        assert_eq!(outputs[&node_id].get_named::<usize>("count"), Some(&3));
+        let map = graph
+            .get_result::<HashMap<String, Content>>(&node_id)
+            .unwrap();
+        assert_eq!(map.len(), 2);
+    }
+
+    /// An implementation of [`Action`] that outputs a label of the given length by name,
+    /// counting its runs.
+    pub struct LabelMapAction(usize, Arc<AtomicUsize>);
+    #[async_trait]
+    impl Action for LabelMapAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            Output::map([("label", Content::sized("x".repeat(self.0)))])
+        }
+    }
+
+    /// Test for the size of an output carrying several named contents.
+    ///
+    /// Node X outputs a label of 20 bytes, more than its maximum output size of 10 bytes, so
+    /// it fails, while Node Y outputs a label of 5 bytes and succeeds.
+    #[test]
+    fn test_named_outputs_size() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut ids = Vec::new();
+        for (name, len) in [("Node X", 20), ("Node Y", 5)] {
+            let mut node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(LabelMapAction(len, Arc::new(AtomicUsize::new(0)))),
+                &mut node_table,
+            );
+            node.set_max_output_size(10);
+            ids.push(node.id());
+            graph.add_node(Box::new(node));
+        }
+
+        assert!(matches!(graph.start(), Ok(false)));
+        let errors = graph.get_errors();
+        assert_eq!(
+            errors[&ids[0]],
+            GraphError::OutputTooLarge("Node X".to_string(), 20).to_string()
+        );
+        assert!(!errors.contains_key(&ids[1]));
+    }
+
+    /// Test for caching and restoring an output carrying several named contents.
+    ///
+    /// The map output by Node X is cached, then saved to a checkpoint, and is still a map
+    /// with its label when Node X is skipped from the cache or from the checkpoint.
+    #[test]
+    fn test_named_outputs_stored() {
+        let path = std::env::temp_dir().join(format!("dagrs-map-{}", std::process::id()));
+        let runs = Arc::new(AtomicUsize::new(0));
+        let build = || {
+            let mut node_table = NodeTable::new();
+            let mut node = DefaultNode::with_action(
+                NodeName::from("Node X"),
+                Box::new(LabelMapAction(2, runs.clone())),
+                &mut node_table,
+            );
+            node.set_cache_key("Node X");
+            let node_id = node.id();
+            (Graph::with_nodes(vec![Box::new(node)]), node_id)
+        };
+        let assert_label = |graph: &Graph, id: NodeId| {
+            let output = &graph.get_outputs()[&id];
+            assert!(matches!(output, Output::Map(_)));
+            assert_eq!(output.get_named::<String>("label").unwrap(), "xx");
+        };
+
+        let (mut graph, node_id) = build();
+        graph.set_cache(Arc::new(crate::MemoryCache::new()));
+        for _ in 0..2 {
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+            assert_label(&graph, node_id);
+        }
+        assert_eq!(graph.get_skipped(), HashSet::from([node_id]));
+        graph.checkpoint_to(&path).unwrap();
+
+        let (mut graph, node_id) = build();
+        graph.resume_from(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_label(&graph, node_id);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    /// Test for the variables published by the nodes while the graph runs.
+    ///
+    /// Node X publishes "token" and Node Y, which depends on it, reads it. Node Z runs
+    /// concurrently, publishing and reading the variables without deadlocking.
+    ///
+    /// The published variables are seen by every lookup, but neither by a clone of the
+    /// environment nor after a reset.
+    #[test]
+    fn test_shared_env() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(PublishAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(ReadTokenAction),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(ReadTokenAction),
+            &mut node_table,
+        );
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id]);
+        graph.set_env(EnvVar::new(node_table));
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<String>(&node1_id).unwrap(), "secret");
+        assert!(graph.env.contains("token"));
+        assert_eq!(*graph.env.require::<String>("token").unwrap(), "secret");
+        assert_eq!(*graph.env.get_arc::<String>("token").unwrap(), "secret");
+        assert!(graph.env.get_or_default::<bool>("reader"));
+        assert!(EnvVar::clone(&graph.env).get::<String>("token").is_none());
+
+        graph.reset();
+        assert!(!graph.env.contains("token"));
+    }
+
+    /// A [`Node`] with a given id and name, doing nothing.
+    struct FixedIdNode(NodeId, NodeName, InChannels, OutChannels);
+    #[async_trait]
+    impl Node for FixedIdNode {
+        fn id(&self) -> NodeId {
+            self.0
+        }
+
+        fn name(&self) -> NodeName {
+            self.1.clone()
+        }
+
+        fn input_channels(&mut self) -> &mut InChannels {
+            &mut self.2
+        }
+
+        fn output_channels(&mut self) -> &mut OutChannels {
+            &mut self.3
+        }
+
+        async fn run(&mut self, _: Arc<EnvVar>) -> Output {
+            Output::empty()
+        }
+    }
+
+    /// Test for detecting the nodes with the same id.
+    #[test]
+    fn test_duplicate_id() {
+        let named = |id, name: &str| -> Box<dyn Node> {
+            Box::new(FixedIdNode(
+                NodeId(id),
+                NodeName::from(name),
+                InChannels::default(),
+                OutChannels::default(),
+            ))
+        };
+        let node = |id| named(id, &format!("Node {}", id));
+        assert!(matches!(
+            Graph::try_with_nodes(vec![node(5), node(6), named(5, "Other")]),
+            Err(GraphError::DuplicateId(NodeId(5), ref first, ref second))
+                if first == "Node 5" && second == "Other"
+        ));
+        let mut graph = Graph::try_with_nodes(vec![node(5), node(6)]).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(matches!(
+            graph.try_add_node(named(6, "Other")),
+            Err(GraphError::DuplicateId(NodeId(6), ref first, ref second))
+                if first == "Node 6" && second == "Other"
+        ));
+        graph.add_edge(NodeId(5), vec![NodeId(6)]);
+        assert!(matches!(graph.start(), Ok(true)));
+
+        // A replaced node keeps its edges, and is not counted twice.
+        let mut graph = Graph::with_nodes(vec![node(5), node(6), node(5)]);
+        graph.add_edge(NodeId(5), vec![NodeId(6)]);
+        graph.add_node(node(6));
+        assert_eq!(graph.nodes.len(), 2);
+        let replaced = graph.nodes.get_mut(&NodeId(6)).unwrap();
+        assert_eq!(replaced.input_channels().channels.len(), 1);
+        assert_eq!(graph.execution_order().unwrap(), ["Node 5", "Node 6"]);
+        assert!(matches!(graph.start(), Ok(true)));
+    }
+
+    /// Test for reading the environment variables while others are published concurrently.
+    ///
+    /// 100 nodes read "base" while 3 nodes publish and remove their own variables.
+    #[test]
+    fn test_shared_env_stress() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let readers: Vec<NodeId> = (0..100)
+            .map(|i| {
+                let node = DefaultNode::with_action(
+                    format!("Reader {}", i),
+                    Box::new(ReadBaseAction),
+                    &mut node_table,
+                );
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        let names = ["x", "y", "z"];
+        for name in names {
+            graph.add_node(Box::new(DefaultNode::with_action(
+                format!("Writer {}", name),
+                Box::new(WriteSharedAction(name)),
+                &mut node_table,
+            )));
+        }
+        let mut env = EnvVar::new(node_table);
+        env.set("base", 2usize);
+        let env = Arc::new(env);
+        graph.set_shared_env(env.clone());
+
+        assert!(matches!(graph.start(), Ok(true)));
+        for id in &readers {
+            assert_eq!(*graph.get_result::<usize>(id).unwrap(), 200);
+        }
+        for name in names {
+            assert_eq!(env.get::<usize>(name), Some(100));
+        }
+        assert!(!env.remove("base"));
+        assert_eq!(env.get::<usize>("base"), Some(2));
+    }
+
+    /// Test for a published counter incremented by concurrent nodes.
+    ///
+    /// 50 nodes each increment "count" 100 times, and no increment is lost. Once the graph is
+    /// reset, the counter starts again from none, while the environment given to the graph
+    /// keeps the count of the first run.
+    #[test]
+    fn test_update_shared() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        for i in 0..50 {
+            graph.add_node(Box::new(DefaultNode::with_action(
+                format!("Node {}", i),
+                Box::new(IncrementAction),
+                &mut node_table,
+            )));
+        }
+        let env = Arc::new(EnvVar::new(node_table));
+        graph.set_shared_env(env.clone());
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(env.get::<usize>("count"), Some(5000));
+
+        graph.reset();
+        assert!(!graph.env.contains("count"));
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(graph.env.get::<usize>("count"), Some(5000));
+        assert_eq!(env.get::<usize>("count"), Some(5000));
+    }
+
+    /// Test for a graph of nodes of different types.
+    ///
+    /// Node X, a [`DefaultNode`], precedes the hand-written Node 1000.
+    #[test]
+    fn test_mixed_nodes() {
+        let mut node_table = NodeTable::new();
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let fixed_id = NodeId(1000);
+        let nodes: Vec<Box<dyn Node>> = vec![
+            Box::new(node),
+            Box::new(FixedIdNode(
+                fixed_id,
+                NodeName::from("Node 1000"),
+                InChannels::default(),
+                OutChannels::default(),
+            )),
+        ];
+        let mut graph = Graph::try_with_nodes(nodes).unwrap();
+        graph.add_edge(node_id, vec![fixed_id]);
+
+        assert_eq!(graph.execution_order().unwrap(), ["Node X", "Node 1000"]);
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(
+            *graph.get_result::<String>(&node_id).unwrap(),
+            "Hello world"
+        );
+    }
+
+    /// Test for the conditional edges.
+    ///
+    /// Node X outputs "Hello world". Node Y only runs if the output is empty, so it is
+    /// skipped, and Node Z only runs if the output is "Hello world", so it runs.
+    #[test]
+    fn test_conditional_edge() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_conditional_edge(node_id, node1_id, |out: &String| out.is_empty());
+        graph.add_conditional_edge(node_id, node2_id, |out: &String| out == "Hello world");
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(graph.get_skipped(), HashSet::from([node1_id]));
+        assert!(graph.get_result::<String>(&node2_id).is_some());
+    }
+
+    /// Test for exporting the timing in the Chrome trace format.
+    ///
+    /// Node X, Node Y and Node Z sleep for 100ms, at most two at the same time. The last node
+    /// runs after one of the others, on its track.
+    #[test]
+    fn test_chrome_trace() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut ids = Vec::new();
+        for name in ["Node X", "Node Y", "Node Z"] {
+            let node = DefaultNode::with_action(
+                NodeName::from(name),
+                Box::new(SleepAction(Duration::from_millis(100))),
+                &mut node_table,
+            );
+            ids.push(node.id());
+            graph.add_node(Box::new(node));
+        }
+        graph.set_max_parallelism(2).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+
+        let trace: serde_json::Value = serde_json::from_str(&graph.to_chrome_trace()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e["ph"] == "X"));
+        assert!(events.iter().all(|e| e["dur"].as_u64().unwrap() >= 100_000));
+        let tids: HashSet<u64> = events.iter().map(|e| e["tid"].as_u64().unwrap()).collect();
+        assert_eq!(tids.len(), 2);
+        let last = events.iter().map(|e| e["ts"].as_u64().unwrap()).max();
+        assert!(last.unwrap() >= 100_000);
+    }
+
+    /// An executor spawning the nodes on a dedicated runtime, counting them.
+    struct CountingExecutor {
+        spawned: AtomicUsize,
+        runtime: Runtime,
+    }
+    impl NodeExecutor for CountingExecutor {
+        fn spawn(&self, fut: crate::NodeFuture) {
+            self.spawned.fetch_add(1, Ordering::SeqCst);
+            self.runtime.spawn(fut);
+        }
+    }
+
+    /// Test for running the nodes with a custom executor.
     ///
-    /// Step 2: add the nodes to graph.
+    /// Node X sends "Hello world" to Node Y, both running on the runtime of the executor,
+    /// which also runs the timer of the deadline.
+    #[test]
+    fn test_executor() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(Arc::new(AtomicUsize::new(0)))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        let executor = Arc::new(CountingExecutor {
+            spawned: AtomicUsize::new(0),
+            runtime: Runtime::new().unwrap(),
+        });
+        graph.set_executor(executor.clone());
+        graph.set_deadline(Duration::from_secs(5));
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(executor.spawned.load(Ordering::SeqCst), 3);
+        let result = graph.get_result::<String>(&node1_id).unwrap();
+        assert_eq!(*result, "Hello world");
+    }
+
+    /// An implementation of [`Action`] that pauses the graph, and returns a String "Paused".
+    pub struct PauseAction(GraphPauseHandle);
+    #[async_trait]
+    impl Action for PauseAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+            self.0.pause();
+            Output::new("Paused".to_string())
+        }
+    }
+
+    /// Test for pausing and resuming a running graph.
     ///
-    /// Step 3: add the edge between Node X and "Node Y.
+    /// Node X pauses the graph, so Node Y, which waits for Node X, does not run until the
+    /// graph is resumed 200ms later.
+    #[test]
+    fn test_pause() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let handle = graph.pause_handle();
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(PauseAction(handle.clone())),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_conditional_edge(node_id, node1_id, |_: &String| true);
+
+        let run = std::thread::spawn(move || graph.start());
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(handle.is_paused());
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        handle.resume();
+        assert!(matches!(run.join().unwrap(), Ok(true)));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    /// Test for the execution report.
     ///
-    /// Step 4: Run the graph and verify the output saved in the graph structure.
+    /// Node X succeeds without sending anything, so its successor Node Y fails. Node Z is
+    /// skipped.
+    #[test]
+    fn test_execution_report() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::new(NodeName::from("Node X"), &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        let mut node2 = DefaultNode::new(NodeName::from("Node Z"), &mut node_table);
+        node2.set_condition(|_, _| false);
+        let node2_id = node2.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id]);
 
+        let report = graph.start_with_report().unwrap();
+        assert!(!report.succeeded());
+        assert_eq!(report.nodes.len(), 3);
+        assert_eq!(report.count(NodeStatus::Succeeded), 1);
+        assert_eq!(report.count(NodeStatus::Failed), 1);
+        assert_eq!(report.count(NodeStatus::Skipped), 1);
+        assert_eq!(report.get(&node_id).unwrap().status, NodeStatus::Succeeded);
+        let failed = report.get(&node1_id).unwrap();
+        assert_eq!(failed.status, NodeStatus::Failed);
+        assert_eq!(failed.name, "Node Y");
+        assert_eq!(failed.attempts, 1);
+        assert_eq!(failed.error.as_deref(), Some("Closed"));
+        assert!(failed.end().unwrap() >= failed.start.unwrap());
+        let skipped = report.get(&node2_id).unwrap();
+        assert_eq!(skipped.status, NodeStatus::Skipped);
+        assert!(skipped.start.is_none());
+        assert!(report.wall_time.is_some());
+    }
+
+    /// Test for getting results by name after a partially failed run.
+    ///
+    /// Node X sends "Hello world" to Node Y and Node Z, and Node Z fails. Node Y has the
+    /// same name as an earlier node, so the later one is used.
     #[test]
-    fn test_graph_execution() {
-        env_logger::init();
+    fn test_get_result_by_name() {
         let mut graph = Graph::new();
         let mut node_table = NodeTable::new();
 
-        let node_name = "Node X";
-        let node = DefaultNode::new(NodeName::from(node_name), &mut node_table);
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(CountedHelloAction(Arc::new(AtomicUsize::new(0)))),
+            &mut node_table,
+        );
         let node_id = node.id();
+        let unused = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Node Y"),
+            Box::new(RecvAction(node_id)),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        let node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 2,
+            }),
+            &mut node_table,
+        );
+        let node2_id = node2.id();
+        graph.add_node(Box::new(unused));
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        graph.add_edge(node_id, vec![node1_id, node2_id]);
 
-        let node1_name = "Node Y";
+        assert!(matches!(graph.start(), Ok(false)));
+        let result = graph.get_result_by_name::<String>("Node Y").unwrap();
+        assert_eq!(*result, "Hello world");
+        assert!(graph.get_result_by_name::<String>("Node Z").is_none());
+        assert!(graph.get_result_by_name::<String>("Node W").is_none());
+        assert!(graph.get_result::<String>(&node2_id).is_none());
+    }
+
+    /// An implementation of [`Action`] that sends the integers from 1 to 100 one by one.
+    pub struct StreamAction;
+    #[async_trait]
+    impl Action for StreamAction {
+        async fn run(
+            &self,
+            _: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            for i in 1..=100 {
+                if let Err(e) = out_channels.send_to_all(Content::new(i)).await {
+                    return Output::error(format!("{:?}", e));
+                }
+            }
+            Output::empty()
+        }
+    }
+
+    /// An implementation of [`Action`] that sums the integers received from the given node
+    /// until its channel is closed.
+    pub struct SumAction(NodeId);
+    #[async_trait]
+    impl Action for SumAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            _: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let mut sum = 0;
+            while let Ok(content) = in_channels.recv_from(&self.0).await {
+                sum += *content.get::<i32>().unwrap();
+            }
+            Output::new(sum)
+        }
+    }
+
+    /// Test for streaming contents between running nodes.
+    ///
+    /// Node X sends 100 integers, more than its channel holds, so Node Y consumes them while
+    /// Node X is still running.
+    #[test]
+    fn test_stream() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(StreamAction),
+            &mut node_table,
+        );
+        let node_id = node.id();
         let node1 = DefaultNode::with_action(
-            NodeName::from(node1_name),
-            HelloAction::new(),
+            NodeName::from("Node Y"),
+            Box::new(SumAction(node_id)),
             &mut node_table,
         );
         let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
 
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<i32>(&node1_id).unwrap(), 5050);
+    }
+
+    /// Test for streaming contents between running nodes under a limited parallelism.
+    ///
+    /// Node X sends 100 integers, more than its channel holds, with a limit of 1 and 2. Node X
+    /// gives its permit back while its channel is full, so Node Y consumes them.
+    #[test]
+    fn test_stream_max_parallelism() {
+        for limit in [1, 2] {
+            let mut graph = Graph::new();
+            let mut node_table = NodeTable::new();
+
+            let node = DefaultNode::with_action(
+                NodeName::from("Node X"),
+                Box::new(StreamAction),
+                &mut node_table,
+            );
+            let node_id = node.id();
+            let node1 = DefaultNode::with_action(
+                NodeName::from("Node Y"),
+                Box::new(SumAction(node_id)),
+                &mut node_table,
+            );
+            let node1_id = node1.id();
+            graph.add_node(Box::new(node));
+            graph.add_node(Box::new(node1));
+            graph.add_edge(node_id, vec![node1_id]);
+
+            graph.set_max_parallelism(limit).unwrap();
+            assert!(matches!(graph.start(), Ok(true)));
+            assert_eq!(*graph.get_result::<i32>(&node1_id).unwrap(), 5050);
+        }
+    }
+
+    /// Test for the durations of the nodes.
+    ///
+    /// Node X sleeps for 100ms. Node Y is skipped with a cached output, so it has no duration.
+    /// Node Z fails once and is retried after 300ms, which its duration does not count.
+    #[test]
+    fn test_durations() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            Box::new(SleepAction(Duration::from_millis(100))),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let mut node1 = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        node1.set_cache_key("Node Y");
+        let node1_id = node1.id();
+        let mut node2 = DefaultNode::with_action(
+            NodeName::from("Node Z"),
+            Box::new(FlakyAction {
+                runs: Arc::new(AtomicUsize::new(0)),
+                succeed_at: 2,
+            }),
+            &mut node_table,
+        );
+        node2.set_retry(
+            RetryPolicy::new(2).with_backoff(RetryBackoff::Fixed(Duration::from_millis(300))),
+        );
+        let node2_id = node2.id();
         graph.add_node(Box::new(node));
         graph.add_node(Box::new(node1));
+        graph.add_node(Box::new(node2));
+        let cache = Arc::new(crate::MemoryCache::new());
+        crate::Cache::put(&*cache, "Node Y", Content::new(()));
+        graph.set_cache(cache);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        let durations = graph.get_durations();
+        assert!(durations[&node_id] >= Duration::from_millis(100));
+        assert!(!durations.contains_key(&node1_id));
+        assert!(durations[&node2_id] < Duration::from_millis(300));
+    }
+
+    /// Test for running only the nodes needed by a target.
+    ///
+    /// In the diamond of Node A, Node B, Node C and Node D, with the extra Node E after
+    /// Node B, only Node A, Node B and Node E run for the target Node E.
+    #[test]
+    fn test_start_until() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let ids: Vec<NodeId> = ["A", "B", "C", "D", "E"]
+            .into_iter()
+            .map(|label| {
+                let node = DefaultNode::with_action(
+                    format!("Node {}", label),
+                    Box::new(LogAction {
+                        label,
+                        log: log.clone(),
+                        recv: None,
+                    }),
+                    &mut node_table,
+                );
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        graph.add_edge(ids[0], vec![ids[1], ids[2]]);
+        graph.add_edge(ids[1], vec![ids[3], ids[4]]);
+        graph.add_edge(ids[2], vec![ids[3]]);
+
+        assert!(matches!(
+            graph.start_until("Node F"),
+            Err(GraphError::NodeNotFound(_))
+        ));
+        assert!(matches!(graph.start_until("Node E"), Ok(true)));
+        let mut labels = log.lock().unwrap().clone();
+        labels.sort();
+        assert_eq!(labels, ["A", "B", "E"]);
+    }
+
+    /// Test for running a target whose successor is not run.
+    ///
+    /// Node X sends 100 integers to Node Y, more than its channel holds, and is lazy in the
+    /// second run. Node Y is not run for the target Node X, which completes anyway.
+    #[test]
+    fn test_start_until_excluded_successor() {
+        for lazy in [false, true] {
+            let mut graph = Graph::new();
+            let mut node_table = NodeTable::new();
+
+            let mut node = DefaultNode::with_action(
+                NodeName::from("Node X"),
+                Box::new(StreamAction),
+                &mut node_table,
+            );
+            if lazy {
+                node = node.lazy();
+            }
+            let node_id = node.id();
+            let node1 = DefaultNode::with_action(
+                NodeName::from("Node Y"),
+                Box::new(SumAction(node_id)),
+                &mut node_table,
+            );
+            let node1_id = node1.id();
+            graph.add_node(Box::new(node));
+            graph.add_node(Box::new(node1));
+            graph.add_edge(node_id, vec![node1_id]);
+            graph.set_deadline(Duration::from_secs(5));
+
+            assert!(matches!(graph.start_until("Node X"), Ok(true)));
+            assert!(graph.get_result::<i32>(&node1_id).is_none());
+
+            // The channels are rebuilt for the next run.
+            graph.reset();
+            assert!(matches!(graph.start(), Ok(true)));
+            assert_eq!(*graph.get_result::<i32>(&node1_id).unwrap(), 5050);
+        }
+    }
+
+    /// Test for rewriting the execution order.
+    ///
+    /// Reversing the order of the independent Node X and Node Y is accepted, while the
+    /// reversed chain of Node X and Node Y is rejected.
+    #[test]
+    fn test_sequence_rewriter() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let ids: Vec<NodeId> = ["X", "Y"]
+            .into_iter()
+            .map(|label| {
+                let node = DefaultNode::with_action(
+                    format!("Node {}", label),
+                    Box::new(LogAction {
+                        label,
+                        log: log.clone(),
+                        recv: None,
+                    }),
+                    &mut node_table,
+                );
+                let id = node.id();
+                graph.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        let first = ids[1];
+        graph.set_sequence_rewriter(move |mut sequence, _| {
+            sequence.sort_by_key(|id| *id != first);
+            sequence
+        });
+        graph = graph.with_max_concurrency(1).unwrap();
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*log.lock().unwrap(), ["Y", "X"]);
+
+        graph.reset();
+        graph.add_edge(ids[0], vec![ids[1]]);
+        assert!(matches!(graph.start(), Err(GraphError::InvalidOrder(_))));
+    }
+
+    /// Test for a node with a precomputed output.
+    ///
+    /// Node A is never run, and both Node B and Node C receive its precomputed output.
+    #[test]
+    fn test_precomputed() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let node_a = DefaultNode::with_action(
+            NodeName::from("Node A"),
+            Box::new(CountedHelloAction(runs.clone())),
+            &mut node_table,
+        );
+        let a_id = node_a.id();
+        let node_b = DefaultNode::with_action(
+            NodeName::from("Node B"),
+            Box::new(RecvAction(a_id)),
+            &mut node_table,
+        );
+        let b_id = node_b.id();
+        let node_c = DefaultNode::with_action(
+            NodeName::from("Node C"),
+            Box::new(RecvAction(a_id)),
+            &mut node_table,
+        );
+        let c_id = node_c.id();
+        graph.add_node(Box::new(node_a));
+        graph.add_node(Box::new(node_b));
+        graph.add_node(Box::new(node_c));
+        graph.add_edge(a_id, vec![b_id, c_id]);
+        graph.set_precomputed("Node A", Output::new("precomputed".to_string()));
+
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        let results = graph.get_results::<String>();
+        assert_eq!(*results[&b_id].clone().unwrap(), "precomputed");
+        assert_eq!(*results[&c_id].clone().unwrap(), "precomputed");
+    }
+
+    /// Test for the results of the leaves.
+    ///
+    /// Node A fans out to the leaves Node B, Node C and Node D. Node C fails and Node D has
+    /// no output, so only Node B has a leaf result.
+    #[test]
+    fn test_leaf_results() {
+        let mut node_table = NodeTable::new();
+
+        let node_a = DefaultNode::new(NodeName::from("Node A"), &mut node_table);
+        let node_b = DefaultNode::with_action(
+            NodeName::from("Node B"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_c = DefaultNode::with_action(
+            NodeName::from("Node C"),
+            Box::new(RecvAction(node_a.id())),
+            &mut node_table,
+        );
+        let node_d = DefaultNode::new(NodeName::from("Node D"), &mut node_table);
+        let ids = [node_a.id(), node_b.id(), node_c.id(), node_d.id()];
+        let mut graph = Graph::with_nodes(vec![
+            Box::new(node_a),
+            Box::new(node_b),
+            Box::new(node_c),
+            Box::new(node_d),
+        ]);
+        graph.add_edge(ids[0], vec![ids[1], ids[2], ids[3]]);
+
+        assert_eq!(graph.leaves(), ids[1..]);
+        assert!(matches!(graph.start(), Ok(false)));
+        let results = graph.get_leaf_results::<String>();
+        assert_eq!(results.len(), 1);
+        assert_eq!(*results[&ids[1]], "Hello world");
+    }
+
+    /// Test for inspecting the nodes before the graph runs.
+    ///
+    /// Node A and Node B both precede Node C.
+    #[test]
+    fn test_iter_nodes() {
+        let mut node_table = NodeTable::new();
+
+        let nodes: Vec<Box<dyn Node>> = ["Node A", "Node B", "Node C"]
+            .into_iter()
+            .map(|name| -> Box<dyn Node> {
+                Box::new(DefaultNode::new(NodeName::from(name), &mut node_table))
+            })
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|node| node.id()).collect();
+        let mut graph = Graph::with_nodes(nodes);
+        graph.add_edge(ids[1], vec![ids[2]]);
+        graph.add_edge(ids[0], vec![ids[2]]);
+
+        let names: Vec<NodeName> = graph.iter_nodes().map(|node| node.name()).collect();
+        assert_eq!(names, ["Node A", "Node B", "Node C"]);
+        assert_eq!(graph.predecessors_of(&ids[2]), ids[..2]);
+        assert!(graph.predecessors_of(&ids[0]).is_empty());
+    }
+
+    /// Test for the nodes affected by a node of a diamond.
+    ///
+    /// Node A precedes Node B and Node C, which both precede Node D.
+    #[test]
+    fn test_descendants() {
+        let mut node_table = NodeTable::new();
+
+        let nodes: Vec<Box<dyn Node>> = ["Node A", "Node B", "Node C", "Node D"]
+            .into_iter()
+            .map(|name| -> Box<dyn Node> {
+                Box::new(DefaultNode::new(NodeName::from(name), &mut node_table))
+            })
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|node| node.id()).collect();
+        let mut graph = Graph::with_nodes(nodes);
+        graph.add_edge(ids[0], vec![ids[1], ids[2]]);
+        graph.add_edge(ids[1], vec![ids[3]]);
+        graph.add_edge(ids[2], vec![ids[3]]);
+
+        assert_eq!(graph.descendants(&ids[0]), ids[1..]);
+        assert_eq!(graph.descendants(&ids[1]), [ids[3]]);
+        assert!(graph.descendants(&ids[3]).is_empty());
+        assert_eq!(graph.ancestors(&ids[3]), ids[..3]);
+        assert_eq!(
+            graph.downstream_of("Node A").unwrap(),
+            ["Node B", "Node C", "Node D"]
+        );
+        assert_eq!(graph.upstream_of("Node C").unwrap(), ["Node A"]);
+        assert!(matches!(
+            graph.upstream_of("Node E"),
+            Err(GraphError::NodeNotFound(_))
+        ));
+
+        graph.init();
+        assert_eq!(graph.descendants(&ids[0]), ids[1..]);
+    }
+
+    /// Test for assembling a graph node by node.
+    ///
+    /// Node 0 precedes Node 1 and Node 2, and Node 1 precedes Node 3. Removing Node 3 leaves
+    /// a valid graph, while removing Node 0 leaves Node 1 and Node 2 depending on it.
+    #[test]
+    fn test_remove_node() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
+
+        let mut ids = Vec::new();
+        for i in 0..4 {
+            let node = DefaultNode::with_action(
+                format!("Node {}", i),
+                HelloAction::new(),
+                &mut node_table,
+            );
+            ids.push(node.id());
+            graph.add_node(Box::new(node));
+        }
+        graph.add_edge(ids[0], vec![ids[1], ids[2]]);
+        graph.add_edge(ids[1], vec![ids[3]]);
+
+        let removed = graph.remove_node(&ids[3]).unwrap();
+        assert_eq!(removed.name(), "Node 3");
+        assert!(graph.remove_node(&ids[3]).is_none());
+        assert_eq!(
+            graph.execution_order().unwrap(),
+            ["Node 0", "Node 1", "Node 2"]
+        );
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(graph.get_results::<String>().len(), 3);
+
+        graph.remove_node(&ids[0]).unwrap();
+        assert!(matches!(graph.dry_run(), Err(GraphError::UnknownNode(id)) if id == ids[0]));
+        assert!(matches!(graph.start(), Err(GraphError::UnknownNode(_))));
+    }
+
+    /// Test for the result of a single node.
+    ///
+    /// The intermediate Node X can be looked up by id or by name, but not as another type.
+    #[test]
+    fn test_get_result() {
+        let mut graph = Graph::new();
+        let mut node_table = NodeTable::new();
 
+        let node = DefaultNode::with_action(
+            NodeName::from("Node X"),
+            HelloAction::new(),
+            &mut node_table,
+        );
+        let node_id = node.id();
+        let node1 = DefaultNode::new(NodeName::from("Node Y"), &mut node_table);
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
         graph.add_edge(node_id, vec![node1_id]);
+        assert!(graph.get_result::<String>(&node_id).is_none());
 
-        graph.run();
-        let out = graph.execute_states[&node1_id].get_output().unwrap();
-        let out: &String = out.get().unwrap();
-        assert_eq!(out, "Hello world");
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(
+            *graph.get_result::<String>(&node_id).unwrap(),
+            "Hello world"
+        );
+        assert_eq!(
+            *graph.get_result_by_name::<String>("Node X").unwrap(),
+            "Hello world"
+        );
+        assert!(graph.get_result::<usize>(&node_id).is_none());
+        assert!(graph.get_result::<String>(&node1_id).is_none());
+        assert!(graph.get_result_by_name::<String>("Node Z").is_none());
     }
 }
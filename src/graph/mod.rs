@@ -1 +1,9 @@
+pub mod abort;
+pub mod builder;
+pub(crate) mod dot;
+pub mod error;
+pub mod executor;
+#[allow(clippy::module_inception)]
 pub mod graph;
+pub mod observer;
+pub mod pause;
@@ -0,0 +1,155 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use log::warn;
+
+use crate::node::node::NodeId;
+
+/// # Graph observer
+///
+/// A [`GraphObserver`] is notified of the lifecycle events of the nodes of a
+/// [`Graph`](super::graph::Graph), e.g. to drive a progress bar or to send webhooks. It is
+/// registered with `Graph::add_observer`. All the methods do nothing by default.
+///
+/// The node events are sent from the tasks running the nodes, so they may arrive from
+/// several threads at the same time. A panic inside an observer is logged, and neither
+/// stops the other observers nor the graph.
+pub trait GraphObserver: Send + Sync {
+    /// The node is spawned, in the execution order of the graph.
+    fn on_node_scheduled(&self, _id: NodeId, _name: &str) {}
+    /// The node starts running, after waiting for its successor if it is lazy.
+    fn on_node_started(&self, _id: NodeId, _name: &str) {}
+    /// The node is skipped without running, right before it completes successfully.
+    fn on_node_skipped(&self, _id: NodeId, _name: &str) {}
+    /// The node completes successfully. `duration` is how long its attempts ran, zero if it
+    /// did not run, e.g. when skipped.
+    fn on_node_succeeded(&self, _id: NodeId, _name: &str, _duration: Duration) {}
+    /// The node fails with the given error, after all its attempts. `duration` is how long
+    /// its attempts ran, zero if it did not run.
+    fn on_node_failed(&self, _id: NodeId, _name: &str, _err: &str, _duration: Duration) {}
+    /// All the nodes of the graph complete, `success` is whether they all succeed.
+    fn on_graph_completed(&self, _success: bool) {}
+}
+
+/// The observers registered with a graph, notified one after the other.
+pub(crate) struct Observers(pub(crate) Vec<Arc<dyn GraphObserver>>);
+
+impl Observers {
+    /// Notify each observer with `f`, catching the panics of the observers.
+    fn notify(&self, f: impl Fn(&dyn GraphObserver)) {
+        for observer in &self.0 {
+            if panic::catch_unwind(AssertUnwindSafe(|| f(observer.as_ref()))).is_err() {
+                warn!("Graph observer panicked, the event is skipped for it.");
+            }
+        }
+    }
+}
+
+impl GraphObserver for Observers {
+    fn on_node_scheduled(&self, id: NodeId, name: &str) {
+        self.notify(|observer| observer.on_node_scheduled(id, name))
+    }
+
+    fn on_node_started(&self, id: NodeId, name: &str) {
+        self.notify(|observer| observer.on_node_started(id, name))
+    }
+
+    fn on_node_skipped(&self, id: NodeId, name: &str) {
+        self.notify(|observer| observer.on_node_skipped(id, name))
+    }
+
+    fn on_node_succeeded(&self, id: NodeId, name: &str, duration: Duration) {
+        self.notify(|observer| observer.on_node_succeeded(id, name, duration))
+    }
+
+    fn on_node_failed(&self, id: NodeId, name: &str, err: &str, duration: Duration) {
+        self.notify(|observer| observer.on_node_failed(id, name, err, duration))
+    }
+
+    fn on_graph_completed(&self, success: bool) {
+        self.notify(|observer| observer.on_graph_completed(success))
+    }
+}
+
+/// # Sampled observer
+///
+/// A [`SampledObserver`] forwards only every Nth scheduled, started, skipped or succeeded
+/// event to another observer, e.g. to drive a progress signal for a huge graph without
+/// flooding it. The failures of the nodes and the completion of the graph are always
+/// forwarded.
+///
+/// # Example
+/// ```rust
+/// use std::sync::Arc;
+/// use dagrs::{Graph, GraphObserver, SampledObserver};
+///
+/// struct Printer;
+/// impl GraphObserver for Printer {}
+///
+/// let mut graph = Graph::new();
+/// graph.set_observer(Arc::new(SampledObserver::new(Arc::new(Printer), 100)));
+/// ```
+pub struct SampledObserver {
+    inner: Arc<dyn GraphObserver>,
+    every: usize,
+    seen: AtomicUsize,
+}
+
+impl SampledObserver {
+    /// Forward one of every `every` scheduled, started, skipped or succeeded events to `inner`. An `every` of
+    /// 0 or 1 forwards all the events.
+    pub fn new(inner: Arc<dyn GraphObserver>, every: usize) -> Self {
+        Self {
+            inner,
+            every: every.max(1),
+            seen: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count an event, and check whether it is sampled.
+    fn sample(&self) -> bool {
+        self.seen
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.every)
+    }
+}
+
+impl GraphObserver for SampledObserver {
+    fn on_node_scheduled(&self, id: NodeId, name: &str) {
+        if self.sample() {
+            self.inner.on_node_scheduled(id, name)
+        }
+    }
+
+    fn on_node_skipped(&self, id: NodeId, name: &str) {
+        if self.sample() {
+            self.inner.on_node_skipped(id, name)
+        }
+    }
+
+    fn on_node_started(&self, id: NodeId, name: &str) {
+        if self.sample() {
+            self.inner.on_node_started(id, name)
+        }
+    }
+
+    fn on_node_succeeded(&self, id: NodeId, name: &str, duration: Duration) {
+        if self.sample() {
+            self.inner.on_node_succeeded(id, name, duration)
+        }
+    }
+
+    fn on_node_failed(&self, id: NodeId, name: &str, err: &str, duration: Duration) {
+        self.inner.on_node_failed(id, name, err, duration)
+    }
+
+    fn on_graph_completed(&self, success: bool) {
+        self.inner.on_graph_completed(success)
+    }
+}
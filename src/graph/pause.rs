@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// # Graph pause handle
+///
+/// A [`GraphPauseHandle`] pauses and resumes a running [`Graph`](super::graph::Graph) from
+/// outside, e.g. for interactive tooling. It is got with `Graph::pause_handle`, and can be
+/// cloned and sent to other threads.
+///
+/// While the graph is paused, no node begins a new run: the nodes wait after their
+/// predecessors, until the graph is resumed. The runs already begun complete normally.
+#[derive(Clone)]
+pub struct GraphPauseHandle {
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl GraphPauseHandle {
+    pub(crate) fn new(paused: Arc<watch::Sender<bool>>) -> Self {
+        Self { paused }
+    }
+
+    /// Pause the graph.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    /// Resume the graph, letting the waiting nodes run.
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    /// Whether the graph is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+}
@@ -1,22 +1,43 @@
 pub mod connection;
 pub mod graph;
 pub mod node;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod utils;
 
 pub use connection::{
     in_channel::{InChannels, RecvErr},
-    information_packet::Content,
+    information_packet::{Content, ContentSize},
     out_channel::{OutChannels, SendErr},
 };
 pub use node::{
-    action::{Action, EmptyAction},
+    action::{Action, DispatchAction, EmptyAction},
     default_node::DefaultNode,
+    graph_node::GraphNode,
     node::*,
+    retry::{RetryBackoff, RetryPolicy},
 };
 
-pub use graph::graph::*;
+pub use async_trait;
+pub use graph::{
+    abort::GraphAbortHandle,
+    builder::GraphBuilder,
+    error::GraphError,
+    executor::{NodeExecutor, NodeFuture, TokioExecutor},
+    graph::*,
+    observer::{GraphObserver, SampledObserver},
+    pause::GraphPauseHandle,
+};
 pub use tokio;
-pub use utils::{env::EnvVar, output::Output};
+pub use utils::{
+    aggregator::ResultAggregator,
+    cache::{Cache, MemoryCache},
+    env::{EnvVar, EnvVarError, EnvVarOverlay, Variable},
+    logger::{FileLogSink, LogRecord, LogSink, MemoryLogSink, NodeLogger},
+    output::Output,
+    progress::Progress,
+    state::{NodeState, NodeStates},
+};
 
 #[cfg(feature = "derive")]
 pub use derive::*;
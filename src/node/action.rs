@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
 
 use async_trait::async_trait;
 
@@ -61,3 +61,62 @@ impl Action for EmptyAction {
         Output::Out(None)
     }
 }
+
+/// An implementation of [`Action`] that selects one of several sub-actions at runtime.
+///
+/// The selector picks the key of the sub-action from the environment variables, and the
+/// selected sub-action runs with the same channels and environment. Its output becomes the
+/// output of the node, and a key without sub-action makes the node fail.
+///
+/// # Example
+/// ```rust
+/// use dagrs::{DefaultNode, DispatchAction, EmptyAction, EnvVar, Graph, NodeName, NodeTable};
+///
+/// let mut node_table = NodeTable::new();
+/// let action = DispatchAction::new(|env: &EnvVar| env.get::<&str>("mode").unwrap())
+///     .with_action("skip", Box::new(EmptyAction));
+/// let node = DefaultNode::with_action(NodeName::from("Node X"), Box::new(action), &mut node_table);
+///
+/// let mut graph = Graph::new();
+/// graph.add_node(Box::new(node));
+/// let mut env = EnvVar::new(node_table);
+/// env.set("mode", "skip");
+/// graph.set_env(env);
+/// assert!(matches!(graph.start(), Ok(true)));
+/// ```
+pub struct DispatchAction<K> {
+    selector: Box<dyn Fn(&EnvVar) -> K + Send + Sync>,
+    actions: HashMap<K, Box<dyn Action>>,
+}
+
+impl<K: Eq + Hash + Debug + Send + Sync> DispatchAction<K> {
+    /// Create a [`DispatchAction`] without sub-actions, selecting them by `selector`.
+    pub fn new(selector: impl Fn(&EnvVar) -> K + Send + Sync + 'static) -> Self {
+        Self {
+            selector: Box::new(selector),
+            actions: HashMap::new(),
+        }
+    }
+
+    /// Add the sub-action selected by `key`.
+    pub fn with_action(mut self, key: K, action: Box<dyn Action>) -> Self {
+        self.actions.insert(key, action);
+        self
+    }
+}
+
+#[async_trait]
+impl<K: Eq + Hash + Debug + Send + Sync> Action for DispatchAction<K> {
+    async fn run(
+        &self,
+        in_channels: &mut InChannels,
+        out_channels: &OutChannels,
+        env: Arc<EnvVar>,
+    ) -> Output {
+        let key = (self.selector)(&env);
+        match self.actions.get(&key) {
+            Some(action) => action.run(in_channels, out_channels, env).await,
+            None => Output::error(format!("No action for key {:?}", key)),
+        }
+    }
+}
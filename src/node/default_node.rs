@@ -1,15 +1,27 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
 
 use crate::{
     connection::{in_channel::InChannels, out_channel::OutChannels},
-    utils::{env::EnvVar, output::Output},
+    utils::{
+        env::{EnvVar, EnvVarOverlay, Variable},
+        output::Output,
+    },
 };
 
 use super::{
     action::{Action, EmptyAction},
     node::{Node, NodeId, NodeName, NodeTable},
+    retry::{RetryBackoff, RetryPolicy},
 };
 
+/// A condition deciding whether a node runs, see [`DefaultNode::set_condition`].
+type Condition = Box<dyn Fn(&EnvVar, &HashMap<NodeId, Output>) -> bool + Send + Sync>;
+
+/// Computes the cache key of a node, see [`DefaultNode::set_cache_key_with`].
+type CacheKey = Box<dyn Fn(&EnvVar, &HashMap<NodeId, Output>) -> String + Send + Sync>;
+
 /// # Default node type
 ///
 /// [`DefaultNode`] is a default implementation of the [`Node`] trait. Users can use this node
@@ -30,7 +42,7 @@ use super::{
 /// ```
 ///
 /// - use the method `with_action`. Required attributes: node's name; [`NodeTable`](for id allocation);
-/// execution logic [`Action`].
+///   execution logic [`Action`].
 ///
 /// ```rust
 /// use dagrs::{NodeName, NodeTable, DefaultNode, EmptyAction};
@@ -49,8 +61,20 @@ pub struct DefaultNode {
     action: Box<dyn Action>,
     in_channels: InChannels,
     out_channels: OutChannels,
+    timeout: Option<Duration>,
+    lazy: bool,
+    retry: RetryPolicy,
+    max_output_size: Option<usize>,
+    cache_key: Option<CacheKey>,
+    post_process: Option<Box<dyn Fn(Output) -> Output + Send + Sync>>,
+    condition: Option<Condition>,
+    priority: i32,
+    resources: Vec<(String, u32)>,
+    env_overrides: EnvVarOverlay,
+    required_env: Vec<String>,
 }
 
+#[async_trait]
 impl Node for DefaultNode {
     fn id(&self) -> NodeId {
         self.id
@@ -68,24 +92,72 @@ impl Node for DefaultNode {
         &mut self.out_channels
     }
 
-    fn run(&mut self, env: Arc<EnvVar>) -> Output {
-        tokio::runtime::Runtime::new().unwrap().block_on(async {
-            self.action
-                .run(&mut self.in_channels, &self.out_channels, env)
-                .await
-        })
+    async fn run(&mut self, env: Arc<EnvVar>) -> Output {
+        let env = if self.env_overrides.is_empty() {
+            env
+        } else {
+            Arc::new(env.with_overlay(&self.env_overrides))
+        };
+        self.action
+            .run(&mut self.in_channels, &self.out_channels, env)
+            .await
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn is_lazy(&self) -> bool {
+        self.lazy
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    fn max_output_size(&self) -> Option<usize> {
+        self.max_output_size
+    }
+
+    fn cache_key(&self, env: &EnvVar, inputs: &HashMap<NodeId, Output>) -> Option<String> {
+        self.cache_key.as_ref().map(|f| f(env, inputs))
+    }
+
+    fn post_process(&self, out: Output) -> Output {
+        match &self.post_process {
+            Some(f) => f(out),
+            None => out,
+        }
+    }
+
+    fn should_run(&self, env: &EnvVar, inputs: &HashMap<NodeId, Output>) -> bool {
+        self.condition.as_ref().is_none_or(|f| f(env, inputs))
+    }
+
+    fn reads_inputs(&self) -> bool {
+        self.cache_key.is_some() || self.condition.is_some()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn resources(&self) -> Vec<(String, u32)> {
+        self.resources.clone()
+    }
+
+    fn required_env(&self) -> Vec<String> {
+        self.required_env
+            .iter()
+            .filter(|name| !self.env_overrides.contains_key(*name))
+            .cloned()
+            .collect()
     }
 }
 
 impl DefaultNode {
     pub fn new(name: NodeName, node_table: &mut NodeTable) -> Self {
-        Self {
-            id: node_table.alloc_id_for(&name),
-            name,
-            action: Box::new(EmptyAction),
-            in_channels: InChannels::default(),
-            out_channels: OutChannels::default(),
-        }
+        Self::with_action(name, Box::new(EmptyAction), node_table)
     }
 
     pub fn with_action(
@@ -99,8 +171,127 @@ impl DefaultNode {
             action,
             in_channels: InChannels::default(),
             out_channels: OutChannels::default(),
+            timeout: None,
+            lazy: false,
+            retry: RetryPolicy::default(),
+            max_output_size: None,
+            cache_key: None,
+            post_process: None,
+            condition: None,
+            priority: 0,
+            resources: Vec::new(),
+            env_overrides: HashMap::new(),
+            required_env: Vec::new(),
         }
     }
+
+    /// Limit how long a run of this node may take. When the timeout elapses, the run is
+    /// cancelled and the node is regarded as failed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.set_timeout(timeout);
+        self
+    }
+
+    /// Set the timeout of this node. See [`DefaultNode::with_timeout`].
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Make this node lazy: if it has a single successor, it only runs once the successor
+    /// has started. See [`Node::is_lazy`].
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Retry a failed run of this node, until it succeeds or `max_attempts` runs in total
+    /// are made. `backoff` decides how long to wait between the attempts.
+    pub fn with_retry(mut self, max_attempts: u32, backoff: RetryBackoff) -> Self {
+        self.set_retry(RetryPolicy::new(max_attempts).with_backoff(backoff));
+        self
+    }
+
+    /// Set the retry policy of this node, e.g. `(max_attempts, delay)` for a fixed delay
+    /// between the attempts. See [`RetryPolicy`].
+    pub fn set_retry(&mut self, policy: impl Into<RetryPolicy>) {
+        self.retry = policy.into();
+    }
+
+    /// Fail this node if its output is larger than `bytes`. See [`Node::max_output_size`].
+    pub fn set_max_output_size(&mut self, bytes: usize) {
+        self.max_output_size = Some(bytes);
+    }
+
+    /// Transform the output of this node with `f`. See [`Node::post_process`].
+    pub fn set_post_process(&mut self, f: impl Fn(Output) -> Output + Send + Sync + 'static) {
+        self.post_process = Some(Box::new(f));
+    }
+
+    /// Cache the output of this node under `key`. See [`Node::cache_key`].
+    pub fn set_cache_key(&mut self, key: impl Into<String>) {
+        let key = key.into();
+        self.cache_key = Some(Box::new(move |_, _| key.clone()));
+    }
+
+    /// Cache the output of this node under a key hashed by `f` from the environment and the
+    /// outputs of the predecessors of this node by their ids, so that the node only runs
+    /// again when they change. The key is made of the name of the node and the hash. The node
+    /// then waits for its predecessors to finish, see [`Node::reads_inputs`] and
+    /// [`Node::cache_key`].
+    pub fn set_cache_key_with(
+        &mut self,
+        f: impl Fn(&EnvVar, &HashMap<NodeId, Output>) -> u64 + Send + Sync + 'static,
+    ) {
+        let name = self.name.clone();
+        self.cache_key = Some(Box::new(move |env, inputs| {
+            format!("{}#{:016x}", name, f(env, inputs))
+        }));
+    }
+
+    /// Skip this node when `f` returns false, given the environment and the outputs of the
+    /// predecessors of this node by their ids. The node then waits for its predecessors to
+    /// finish, see [`Node::reads_inputs`] and [`Node::should_run`].
+    pub fn set_condition(
+        &mut self,
+        f: impl Fn(&EnvVar, &HashMap<NodeId, Output>) -> bool + Send + Sync + 'static,
+    ) {
+        self.condition = Some(Box::new(f));
+    }
+
+    /// Set the priority of this node. See [`Node::priority`].
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    /// Give the action of this node the variable `name` set to `var`, instead of the one of
+    /// the graph. The other nodes still see the variables of the graph.
+    pub fn set_env_override<H: Send + Sync + 'static>(&mut self, name: &str, var: H) {
+        self.env_overrides
+            .insert(name.to_string(), Variable::new(var));
+    }
+
+    /// Give the action of this node the variables of `env` first, falling back to the ones of
+    /// the graph for the variables `env` does not set. The [`NodeTable`] of `env` is ignored,
+    /// and the variables of `env` replace the overrides set before with the same names.
+    ///
+    /// [`NodeTable`]: crate::NodeTable
+    pub fn set_env(&mut self, env: EnvVar) {
+        self.env_overrides.extend(env.into_variables());
+    }
+
+    /// Require the graph to set the environment variable `name` before this node runs,
+    /// unless this node overrides it. See [`Node::required_env`].
+    pub fn require_env(&mut self, name: impl Into<String>) {
+        self.required_env.push(name.into());
+    }
+
+    /// Take `amount` of the resource `name` of the graph while this node runs, replacing the
+    /// amount required before. See [`Node::resources`].
+    pub fn require_resource(&mut self, name: impl Into<String>, amount: u32) {
+        let name = name.into();
+        self.resources.retain(|(required, _)| *required != name);
+        self.resources.push((name, amount));
+    }
 }
 
 #[cfg(test)]
@@ -126,7 +317,7 @@ mod test_default_node {
 
     impl HelloAction {
         pub fn new() -> Box<Self> {
-            Box::new(Self::default())
+            Box::new(Self)
         }
     }
 
@@ -150,7 +341,11 @@ mod test_default_node {
         assert_eq!(node_table.get(node_name).unwrap(), &node.id());
 
         let env = Arc::new(EnvVar::new(node_table));
-        let out = node.run(env).get_out().unwrap();
+        let out = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(node.run(env))
+            .get_out()
+            .unwrap();
         let out: &String = out.get().unwrap();
         assert_eq!(out, "Hello world");
     }
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use log::warn;
+
+use crate::{
+    connection::{in_channel::InChannels, out_channel::OutChannels},
+    graph::graph::Graph,
+    utils::{env::EnvVar, output::Output},
+};
+
+use super::node::{Node, NodeId, NodeName, NodeTable};
+
+/// # Graph node type
+///
+/// [`GraphNode`] is a node whose run starts an inner [`Graph`], so that a complex graph can
+/// be composed of smaller ones instead of being flattened.
+///
+/// The inner graph shares the environment variables of the outer graph. If the inner graph
+/// has a single leaf node, the output of that node is the output of the [`GraphNode`], and
+/// is sent to its successors in the outer graph, otherwise the output is empty. If a node of
+/// the inner graph fails, the [`GraphNode`] fails with the errors of the inner graph.
+///
+/// The inner graph runs on the runtime of the outer graph, not on a runtime of its own.
+///
+/// ```rust
+/// use dagrs::{DefaultNode, Graph, GraphNode, NodeName, NodeTable};
+///
+/// let mut node_table = NodeTable::new();
+/// let mut inner = Graph::new();
+/// inner.add_node(Box::new(DefaultNode::new(NodeName::from("Inner"), &mut node_table)));
+///
+/// let mut graph = Graph::new();
+/// let node = GraphNode::new(NodeName::from("Outer"), inner, &mut node_table);
+/// graph.add_node(Box::new(node));
+/// assert!(graph.start().unwrap());
+/// ```
+pub struct GraphNode {
+    id: NodeId,
+    name: NodeName,
+    graph: Graph,
+    in_channels: InChannels,
+    out_channels: OutChannels,
+}
+
+#[async_trait]
+impl Node for GraphNode {
+    fn id(&self) -> NodeId {
+        self.id
+    }
+
+    fn name(&self) -> NodeName {
+        self.name.clone()
+    }
+
+    fn input_channels(&mut self) -> &mut InChannels {
+        &mut self.in_channels
+    }
+
+    fn output_channels(&mut self) -> &mut OutChannels {
+        &mut self.out_channels
+    }
+
+    async fn run(&mut self, env: Arc<EnvVar>) -> Output {
+        self.graph.reset();
+        self.graph.set_shared_env(env);
+        let out = self.run_inner().await;
+        if let Some(content) = out.get_out() {
+            if let Err(e) = self.out_channels.send_to_all(content).await {
+                warn!(
+                    "Failed to send the output of graph node [name: {}]: {:?}",
+                    self.name, e
+                );
+            }
+        }
+        out
+    }
+}
+
+impl GraphNode {
+    pub fn new(name: NodeName, graph: Graph, node_table: &mut NodeTable) -> Self {
+        Self {
+            id: node_table.alloc_id_for(&name),
+            name,
+            graph,
+            in_channels: InChannels::default(),
+            out_channels: OutChannels::default(),
+        }
+    }
+
+    /// Get the inner graph, e.g. to read the results of its nodes after a run.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Run the inner graph, and get the output of the graph node.
+    async fn run_inner(&mut self) -> Output {
+        match self.graph.start_async().await {
+            Ok(true) => match self.graph.leaves()[..] {
+                [leaf] => self
+                    .graph
+                    .get_outputs()
+                    .remove(&leaf)
+                    .unwrap_or_else(Output::empty),
+                _ => Output::empty(),
+            },
+            Ok(false) => {
+                let mut errors: Vec<String> = self.graph.get_errors().into_values().collect();
+                errors.sort();
+                Output::error(format!("Inner graph failed: {}", errors.join("; ")))
+            }
+            Err(e) => Output::error(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_graph_node {
+
+    use std::sync::Arc;
+
+    use crate::{
+        Action, Content, DefaultNode, EnvVar, Graph, InChannels, Node, NodeId, NodeName, NodeTable,
+        OutChannels, Output,
+    };
+
+    use super::GraphNode;
+
+    use async_trait::async_trait;
+
+    /// An implementation of [`Action`] that returns the environment variable "greeting".
+    pub struct GreetAction;
+    #[async_trait]
+    impl Action for GreetAction {
+        async fn run(&self, _: &mut InChannels, _: &OutChannels, env: Arc<EnvVar>) -> Output {
+            match env.get::<String>("greeting") {
+                Some(greeting) => Output::new(greeting),
+                None => Output::error("no greeting".to_string()),
+            }
+        }
+    }
+
+    /// An implementation of [`Action`] that appends its suffix to the String received from its
+    /// predecessor, if any, and sends the result to its successors. It fails on an empty
+    /// suffix.
+    pub struct AppendAction(&'static str);
+    #[async_trait]
+    impl Action for AppendAction {
+        async fn run(
+            &self,
+            in_channels: &mut InChannels,
+            out_channels: &OutChannels,
+            _: Arc<EnvVar>,
+        ) -> Output {
+            let mut s = String::new();
+            let predecessors: Vec<NodeId> = in_channels.channels.keys().copied().collect();
+            for id in predecessors {
+                match in_channels.recv_from(&id).await {
+                    Ok(content) => s.push_str(content.get::<String>().unwrap()),
+                    Err(e) => return Output::error(format!("{:?}", e)),
+                }
+            }
+            if self.0.is_empty() {
+                return Output::error("empty suffix".to_string());
+            }
+            s.push_str(self.0);
+            let _ = out_channels.send_to_all(Content::new(s.clone())).await;
+            Output::new(s)
+        }
+    }
+
+    /// Build an outer graph where a graph node running a chain of 3 nodes with the given
+    /// suffixes precedes a node appending "!". Returns the graph and the ids of its nodes.
+    fn nested_graph(suffixes: [&'static str; 3]) -> (Graph, [NodeId; 2]) {
+        let mut node_table = NodeTable::new();
+        let mut inner = Graph::new();
+        let ids: Vec<NodeId> = suffixes
+            .into_iter()
+            .map(|suffix| {
+                let node = DefaultNode::with_action(
+                    format!("Inner {}", suffix),
+                    Box::new(AppendAction(suffix)),
+                    &mut node_table,
+                );
+                let id = node.id();
+                inner.add_node(Box::new(node));
+                id
+            })
+            .collect();
+        inner.add_edge(ids[0], vec![ids[1]]);
+        inner.add_edge(ids[1], vec![ids[2]]);
+
+        let mut graph = Graph::new();
+        let node = GraphNode::new(NodeName::from("Outer"), inner, &mut node_table);
+        let node_id = node.id();
+        let node1 = DefaultNode::with_action(
+            NodeName::from("Last"),
+            Box::new(AppendAction("!")),
+            &mut node_table,
+        );
+        let node1_id = node1.id();
+        graph.add_node(Box::new(node));
+        graph.add_node(Box::new(node1));
+        graph.add_edge(node_id, vec![node1_id]);
+        (graph, [node_id, node1_id])
+    }
+
+    /// Test for sending the output of a graph node to its successors.
+    ///
+    /// The inner chain outputs "abc", and the node after the graph node appends "!" to it.
+    /// A failure in the middle of the chain fails the graph node and its successor.
+    #[test]
+    fn nested_graph_node() {
+        let (mut graph, [node_id, node1_id]) = nested_graph(["a", "b", "c"]);
+        assert!(matches!(graph.start(), Ok(true)));
+        assert_eq!(*graph.get_result::<String>(&node_id).unwrap(), "abc");
+        assert_eq!(*graph.get_result::<String>(&node1_id).unwrap(), "abc!");
+
+        let (mut graph, [node_id, node1_id]) = nested_graph(["a", "", "c"]);
+        assert!(matches!(graph.start(), Ok(false)));
+        let errors = graph.get_errors();
+        assert!(errors[&node_id].starts_with("Inner graph failed: "));
+        assert!(errors[&node_id].contains("empty suffix"));
+        assert!(errors.contains_key(&node1_id));
+    }
+
+    /// Test for running a graph inside a node.
+    ///
+    /// The inner graph reads the environment variables of the outer graph, and its only
+    /// node's output is the output of the graph node.
+    #[test]
+    fn run_graph_node() {
+        let mut node_table = NodeTable::new();
+        let mut inner = Graph::new();
+        let node = DefaultNode::with_action(
+            NodeName::from("Inner"),
+            Box::new(GreetAction),
+            &mut node_table,
+        );
+        inner.add_node(Box::new(node));
+
+        let mut graph = Graph::new();
+        let node = GraphNode::new(NodeName::from("Outer"), inner, &mut node_table);
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+        let mut env = EnvVar::new(node_table);
+        env.set("greeting", "Hello world".to_string());
+        graph.set_env(env);
+
+        assert!(matches!(graph.start(), Ok(true)));
+        let result = graph.get_result::<String>(&node_id).unwrap();
+        assert_eq!(*result, "Hello world");
+    }
+
+    /// Test for the failures inside a graph node.
+    #[test]
+    fn fail_graph_node() {
+        let mut node_table = NodeTable::new();
+        let mut inner = Graph::new();
+        let node = DefaultNode::with_action(
+            NodeName::from("Inner"),
+            Box::new(GreetAction),
+            &mut node_table,
+        );
+        inner.add_node(Box::new(node));
+
+        let mut graph = Graph::new();
+        let node = GraphNode::new(NodeName::from("Outer"), inner, &mut node_table);
+        let node_id = node.id();
+        graph.add_node(Box::new(node));
+
+        assert!(matches!(graph.start(), Ok(false)));
+        assert_eq!(
+            graph.get_errors()[&node_id],
+            "Inner graph failed: no greeting"
+        );
+    }
+}
@@ -1,4 +1,7 @@
 pub mod action;
 pub mod default_node;
+pub mod graph_node;
 pub mod id_allocate;
+#[allow(clippy::module_inception)]
 pub mod node;
+pub mod retry;
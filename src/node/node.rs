@@ -1,11 +1,13 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
 
 use crate::{
     connection::{in_channel::InChannels, out_channel::OutChannels},
     utils::{env::EnvVar, output::Output},
 };
 
-use super::id_allocate::alloc_id;
+use super::{id_allocate::alloc_id, retry::RetryPolicy};
 
 ///# The [`Node`] trait
 ///
@@ -15,6 +17,7 @@ use super::id_allocate::alloc_id;
 /// Nodes can communicate with others asynchronously through [`InChannels`] and [`OutChannels`].
 ///
 /// In addition to the above properties, users can also customize some other attributes.
+#[async_trait]
 pub trait Node: Send + Sync {
     /// id is the unique identifier of each node, it will be assigned by the [`NodeTable`]
     /// when creating a new node, you can find this node through this identifier.
@@ -26,10 +29,89 @@ pub trait Node: Send + Sync {
     /// Output Channels of this node.
     fn output_channels(&mut self) -> &mut OutChannels;
     /// Execute a run of this node.
-    fn run(&mut self, env: Arc<EnvVar>) -> Output;
+    async fn run(&mut self, env: Arc<EnvVar>) -> Output;
+    /// The maximum duration a run of this node may take. A node that runs longer is
+    /// cancelled and regarded as failed. `None` means no limit.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+    /// A lazy node with exactly one successor delays its run until the successor has
+    /// started, to shorten the time its output waits in memory before being consumed.
+    /// Lazy nodes with several successors run eagerly.
+    fn is_lazy(&self) -> bool {
+        false
+    }
+    /// How often a failed run of this node is retried, and how long to wait in between.
+    /// A failed run is retried until it succeeds or the attempts are exhausted.
+    ///
+    /// The contents received by a failed attempt are received again by the next one, before
+    /// the new contents. The contents sent by a node that may be retried are held until an
+    /// attempt succeeds, so its successors only receive those of the attempt that succeeds,
+    /// once it completes. If all the attempts fail, none of them is sent.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+    /// The maximum size in bytes of the output of this node. A node whose output is larger
+    /// fails instead, and the larger contents it sends to its successors are not delivered,
+    /// the send returns [`SendErr::TooLarge`].
+    ///
+    /// Only the contents of known size, built with [`Content::sized`], are checked. The
+    /// contents built with `Content::new` are always output and sent, whatever their size.
+    ///
+    /// [`SendErr::TooLarge`]: crate::connection::out_channel::SendErr::TooLarge
+    /// [`Content::sized`]: crate::Content::sized
+    fn max_output_size(&self) -> Option<usize> {
+        None
+    }
+    /// The key of the output of this node in the cache of the graph, given the environment
+    /// of the graph and the outputs of the predecessors of this node by their ids, see
+    /// [`Node::reads_inputs`]. A node whose output is cached is not run, and the cached output
+    /// is sent to its successors instead. `None` means the output is never cached.
+    fn cache_key(&self, _env: &EnvVar, _inputs: &HashMap<NodeId, Output>) -> Option<String> {
+        None
+    }
+    /// Transform the output of the last attempt to run this node, before it is stored.
+    /// The default implementation returns the output unchanged.
+    fn post_process(&self, out: Output) -> Output {
+        out
+    }
+    /// Whether this node runs, given the environment of the graph and the outputs of the
+    /// predecessors of this node by their ids, see [`Node::reads_inputs`]. A node that does
+    /// not run is skipped: it succeeds with an empty output and its output channels are
+    /// closed, so its successors still run with the inputs of their other predecessors.
+    fn should_run(&self, _env: &EnvVar, _inputs: &HashMap<NodeId, Output>) -> bool {
+        true
+    }
+    /// Whether [`Node::cache_key`] and [`Node::should_run`] read the outputs of the
+    /// predecessors. If so, this node waits for all its predecessors to finish before it
+    /// decides to run, so they must not send it more contents than its channels hold.
+    /// Otherwise the outputs given are empty.
+    fn reads_inputs(&self) -> bool {
+        false
+    }
+    /// The priority of this node. Among the nodes ready to start at the same time, those
+    /// of higher priority are started first, which matters when the parallelism of the
+    /// graph is limited. The default priority is 0.
+    ///
+    /// The priority is only a hint: without a limit all the nodes are spawned at once, so it
+    /// only orders their spawning and the logged execution order.
+    fn priority(&self) -> i32 {
+        0
+    }
+    /// The resources this node needs to run, by the name of their pool in the graph, with
+    /// the amount taken from each. See `Graph::set_resource`.
+    fn resources(&self) -> Vec<(String, u32)> {
+        Vec::new()
+    }
+
+    /// The names of the environment variables this node reads, which the graph must set
+    /// before it runs. See `Graph::dry_run`.
+    fn required_env(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct NodeId(pub(crate) usize);
 
 pub type NodeName = String;
@@ -50,7 +132,7 @@ impl NodeTable {
         let id = alloc_id();
         log::debug!("alloc id {:?} for {:?}", id, name);
 
-        if let Some(v) = self.0.insert(name.to_string(), id.clone()) {
+        if let Some(v) = self.0.insert(name.to_string(), id) {
             log::warn!("Node {} is already allocated with id {:?}.", name, v);
         };
         id
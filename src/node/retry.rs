@@ -0,0 +1,192 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// # Retry backoff
+///
+/// [`RetryBackoff`] decides how long to wait before retrying a failed run of a node.
+/// - None: retry immediately.
+/// - Fixed(delay): always wait for `delay`.
+/// - Exponential: wait for `base` first, and multiply the delay by `multiplier` for each
+///   following retry, but never wait longer than `max`. A negative or NaN `multiplier`
+///   counts as 0.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use dagrs::RetryBackoff;
+///
+/// let backoff = RetryBackoff::Exponential {
+///     base: Duration::from_millis(100),
+///     multiplier: 2.0,
+///     max: Duration::from_secs(1),
+/// };
+/// assert_eq!(backoff.delay(1), Duration::from_millis(100));
+/// assert_eq!(backoff.delay(3), Duration::from_millis(400));
+/// assert_eq!(backoff.delay(5), Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryBackoff {
+    None,
+    Fixed(Duration),
+    Exponential {
+        base: Duration,
+        multiplier: f64,
+        max: Duration,
+    },
+}
+
+impl RetryBackoff {
+    /// The delay before the retry following the given failed attempt, which starts from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match self {
+            RetryBackoff::None => Duration::ZERO,
+            RetryBackoff::Fixed(delay) => *delay,
+            RetryBackoff::Exponential {
+                base,
+                multiplier,
+                max,
+            } => {
+                let exp = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+                let secs = base.as_secs_f64() * multiplier.max(0.0).powi(exp);
+                Duration::try_from_secs_f64(secs).map_or(*max, |delay| delay.min(*max))
+            }
+        }
+    }
+}
+
+/// # Retry policy
+///
+/// [`RetryPolicy`] decides how often a failed node is run again, and how long to wait between
+/// the attempts: the delay given by the [`RetryBackoff`], plus a random jitter of up to
+/// `jitter`, which keeps nodes failing together from retrying at the same moment.
+///
+/// The retries stop at whichever comes first: the maximum number of attempts, or the
+/// maximum duration of all the attempts and the delays between them.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use dagrs::{RetryBackoff, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(3)
+///     .with_backoff(RetryBackoff::Fixed(Duration::from_millis(100)))
+///     .with_jitter(Duration::from_millis(10));
+/// let delay = policy.delay(1);
+/// assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(110));
+///
+/// // At most 3 attempts, 100ms apart.
+/// let policy = RetryPolicy::from((3, Duration::from_millis(100)));
+/// assert_eq!(policy.delay(2), Duration::from_millis(100));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: RetryBackoff,
+    jitter: Duration,
+    max_duration: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Run a node at most `max_attempts` times in total, retrying immediately. A node is
+    /// always run at least once.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff: RetryBackoff::None,
+            jitter: Duration::ZERO,
+            max_duration: None,
+        }
+    }
+
+    /// Wait between the attempts as given by `backoff`.
+    pub fn with_backoff(mut self, backoff: RetryBackoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Add a random jitter of up to `jitter` to each delay.
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stop retrying once a retry would start after `max_duration` since the first attempt,
+    /// even if attempts remain.
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Whether a retry may start after `elapsed` since the first attempt.
+    pub fn allows_retry_after(&self, elapsed: Duration) -> bool {
+        self.max_duration.is_none_or(|max| elapsed <= max)
+    }
+
+    /// The maximum number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The delay before the retry following the given failed attempt, which starts from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff.delay(attempt);
+        if self.jitter.is_zero() {
+            return delay;
+        }
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(attempt);
+        let ratio = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+        delay.saturating_add(self.jitter.mul_f64(ratio))
+    }
+}
+
+impl From<(u32, Duration)> for RetryPolicy {
+    /// Run a node at most `max_attempts` times, waiting for a fixed delay between the attempts.
+    fn from((max_attempts, delay): (u32, Duration)) -> Self {
+        Self::new(max_attempts).with_backoff(RetryBackoff::Fixed(delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryBackoff;
+
+    fn exponential(base: Duration, multiplier: f64) -> RetryBackoff {
+        RetryBackoff::Exponential {
+            base,
+            multiplier,
+            max: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn invalid_multiplier() {
+        let base = Duration::from_millis(100);
+        for multiplier in [-2.0, f64::NAN, f64::NEG_INFINITY] {
+            let backoff = exponential(base, multiplier);
+            assert_eq!(backoff.delay(1), base);
+            assert_eq!(backoff.delay(2), Duration::ZERO);
+        }
+        let backoff = exponential(Duration::ZERO, f64::INFINITY);
+        assert_eq!(backoff.delay(2), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn overflowing_delay() {
+        let backoff = exponential(Duration::from_secs(u64::MAX), 10.0);
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        let backoff = exponential(Duration::from_millis(100), 10.0);
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,185 @@
+//! # Test doubles
+//!
+//! Actions to test the wiring of a graph without real logic. They are cheap handles: keep a
+//! clone of an action to inspect it once the graph has run.
+//!
+//! This module is only built with the `testing` feature, or for the tests of this crate.
+//!
+//! ```rust
+//! use dagrs::{testing::{MockAction, SpyAction}, Graph};
+//!
+//! let mock = MockAction::returning(42);
+//! let spy = SpyAction::new();
+//! let mut builder = Graph::builder();
+//! let a = builder.add_node("Node A", Box::new(mock.clone()));
+//! let b = builder.add_node("Node B", Box::new(spy.clone()));
+//! builder.add_dependency(a, b);
+//! assert!(builder.build().unwrap().start().unwrap());
+//!
+//! assert_eq!(mock.call_count(), 1);
+//! assert!(spy.received_input_from(a));
+//! assert_eq!(spy.received_from::<i32>(&a), Some(42));
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::{
+    connection::{in_channel::InChannels, information_packet::Content, out_channel::OutChannels},
+    node::{action::Action, node::NodeId},
+    utils::{env::EnvVar, output::Output},
+};
+
+/// An [`Action`] counting its runs, which sends the content it is given, if any, to all its
+/// successors and outputs it.
+#[derive(Clone, Default)]
+pub struct MockAction {
+    content: Option<Content>,
+    calls: Arc<Mutex<Vec<Vec<NodeId>>>>,
+}
+
+impl MockAction {
+    /// A mock with an empty output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A mock sending and outputting `value`.
+    pub fn returning<H: Send + Sync + 'static>(value: H) -> Self {
+        Self {
+            content: Some(Content::new(value)),
+            ..Self::default()
+        }
+    }
+
+    /// The number of times the mock has run.
+    pub fn call_count(&self) -> usize {
+        self.calls.lock().unwrap().len()
+    }
+
+    /// The ids of the predecessors of the node at each run, sorted.
+    pub fn calls(&self) -> Vec<Vec<NodeId>> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Action for MockAction {
+    async fn run(
+        &self,
+        in_channels: &mut InChannels,
+        out_channels: &OutChannels,
+        _: Arc<EnvVar>,
+    ) -> Output {
+        let mut predecessors: Vec<NodeId> = in_channels.channels.keys().copied().collect();
+        predecessors.sort();
+        self.calls.lock().unwrap().push(predecessors);
+        match &self.content {
+            Some(content) => {
+                let _ = out_channels.send_to_all(content.clone()).await;
+                Output::Out(Some(content.clone()))
+            }
+            None => Output::empty(),
+        }
+    }
+}
+
+/// An [`Action`] receiving one content from each of its predecessors, and recording them.
+/// A predecessor closing its channel without sending is recorded as sending nothing.
+#[derive(Clone, Default)]
+pub struct SpyAction {
+    calls: Arc<Mutex<usize>>,
+    received: Arc<Mutex<Vec<(NodeId, Content)>>>,
+}
+
+impl SpyAction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of times the spy has run.
+    pub fn call_count(&self) -> usize {
+        *self.calls.lock().unwrap()
+    }
+
+    /// The contents received, with the ids of their senders, in the order they arrived.
+    pub fn received(&self) -> Vec<(NodeId, Content)> {
+        self.received.lock().unwrap().clone()
+    }
+
+    /// Whether a content was received from the node with the given id.
+    pub fn received_input_from(&self, id: NodeId) -> bool {
+        self.received
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(from, _)| *from == id)
+    }
+
+    /// Get a clone of the last value of type `H` received from the node with the given id.
+    pub fn received_from<H: Clone + 'static>(&self, id: &NodeId) -> Option<H> {
+        self.received
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(from, _)| from == id)
+            .and_then(|(_, content)| content.try_clone_as::<H>())
+    }
+}
+
+#[async_trait]
+impl Action for SpyAction {
+    async fn run(&self, in_channels: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+        *self.calls.lock().unwrap() += 1;
+        let mut predecessors: Vec<NodeId> = in_channels.channels.keys().copied().collect();
+        predecessors.sort();
+        for id in predecessors {
+            if let Ok(content) = in_channels.recv_from(&id).await {
+                self.received.lock().unwrap().push((id, content));
+            }
+        }
+        Output::empty()
+    }
+}
+
+#[cfg(test)]
+mod test_testing {
+
+    use crate::{Graph, NodeId};
+
+    use super::{MockAction, SpyAction};
+
+    /// Test for checking the wiring of a graph with mocks and spies.
+    ///
+    /// Node A and Node B send to Node C. Node B is skipped, so Node C only receives from
+    /// Node A, and each node runs once.
+    #[test]
+    fn mock_and_spy() {
+        let mock = MockAction::returning(1);
+        let spy = SpyAction::new();
+        let mut builder = Graph::builder();
+        let a = builder.add_node("Node A", Box::new(mock.clone()));
+        let b = builder.add_boxed_node(|node_table| {
+            let mut node = crate::DefaultNode::with_action(
+                crate::NodeName::from("Node B"),
+                Box::new(MockAction::returning(2)),
+                node_table,
+            );
+            node.set_condition(|_, _| false);
+            Box::new(node)
+        });
+        let c = builder.add_node("Node C", Box::new(spy.clone()));
+        builder.add_dependency(a, c).add_dependency(b, c);
+        let mut graph = builder.build().unwrap();
+
+        assert!(graph.start().unwrap());
+        assert_eq!(mock.calls(), [Vec::<NodeId>::new()]);
+        assert_eq!(spy.call_count(), 1);
+        assert!(spy.received_input_from(a));
+        assert!(!spy.received_input_from(b));
+        assert_eq!(spy.received_from::<i32>(&a), Some(1));
+        assert_eq!(spy.received().len(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+use crate::node::node::NodeId;
+
+use super::output::Output;
+
+/// # Result aggregator
+///
+/// [`ResultAggregator`] collects the outputs of several runs of the same `Graph`, e.g. a
+/// parameter sweep that runs the graph once for each value of an environment variable.
+/// The outputs of each run (see `Graph::get_outputs`) are tagged with a label, so that
+/// the results of a node can be compared across the runs.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use dagrs::{Output, ResultAggregator};
+/// # let node_id = dagrs::NodeTable::new().alloc_id_for("node");
+///
+/// let mut aggregator = ResultAggregator::new();
+/// aggregator.add("a", HashMap::from([(node_id, Output::new(1))]));
+/// aggregator.add("b", HashMap::from([(node_id, Output::new(2))]));
+///
+/// let values = aggregator.values_of::<i32>(&node_id);
+/// assert_eq!(*values[&"b"], 2);
+/// assert!(!aggregator.all_equal::<i32>(&node_id));
+/// ```
+#[derive(Debug)]
+pub struct ResultAggregator<L: Eq + Hash> {
+    runs: HashMap<L, HashMap<NodeId, Output>>,
+}
+
+impl<L: Eq + Hash> Default for ResultAggregator<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Eq + Hash> ResultAggregator<L> {
+    /// Create an empty [`ResultAggregator`].
+    pub fn new() -> Self {
+        Self {
+            runs: HashMap::new(),
+        }
+    }
+
+    /// Add the outputs of a run tagged with `label`. The outputs of an earlier run with the
+    /// same label are replaced.
+    pub fn add(&mut self, label: L, outputs: HashMap<NodeId, Output>) {
+        self.runs.insert(label, outputs);
+    }
+
+    /// Get the outputs of the run tagged with `label`.
+    pub fn get(&self, label: &L) -> Option<&HashMap<NodeId, Output>> {
+        self.runs.get(label)
+    }
+
+    /// Get the combined view of all the runs.
+    pub fn runs(&self) -> &HashMap<L, HashMap<NodeId, Output>> {
+        &self.runs
+    }
+
+    /// Get the successful outputs of the given node in every run, if they are of type `T`.
+    pub fn values_of<T: Send + Sync + 'static>(&self, id: &NodeId) -> HashMap<&L, Arc<T>> {
+        self.runs
+            .iter()
+            .filter_map(|(label, outputs)| {
+                let value = outputs.get(id)?.get_out()?.into_inner()?;
+                Some((label, value))
+            })
+            .collect()
+    }
+
+    /// Check whether the given node produces equal outputs of type `T` in all the runs.
+    /// A run where the node fails or produces no output of type `T` counts as a difference.
+    pub fn all_equal<T: PartialEq + Send + Sync + 'static>(&self, id: &NodeId) -> bool {
+        let values = self.values_of::<T>(id);
+        if values.len() < self.runs.len() {
+            return false;
+        }
+        let mut values = values.into_values();
+        match values.next() {
+            Some(first) => values.all(|value| value == first),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::connection::information_packet::Content;
+
+/// # Output cache
+///
+/// A [`Cache`] stores the outputs of the nodes by their cache keys (see `Node::cache_key`),
+/// so that a node whose output is cached is not run again. The cache of a `Graph` is set by
+/// `Graph::set_cache`, and kept when the graph is reset.
+pub trait Cache: Send + Sync {
+    /// Get the output cached under `key`.
+    fn get(&self, key: &str) -> Option<Content>;
+    /// Cache the output under `key`, replacing the one cached before.
+    fn put(&self, key: &str, content: Content);
+}
+
+/// An in-memory implementation of [`Cache`].
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    contents: Mutex<HashMap<String, Content>>,
+}
+
+impl MemoryCache {
+    /// Create an empty [`MemoryCache`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Cache for MemoryCache {
+    fn get(&self, key: &str) -> Option<Content> {
+        self.contents.lock().unwrap().get(key).cloned()
+    }
+
+    fn put(&self, key: &str, content: Content) {
+        self.contents
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), content);
+    }
+}
@@ -0,0 +1,78 @@
+//! Checkpoint of a graph run
+//!
+//! A checkpoint records the nodes that completed successfully in a run, by their names, so
+//! that a later run can skip them. The file is a JSON array of entries like
+//! `{"name": "Node X", "restorable": true, "output": "..."}`.
+//!
+//! Only the outputs of type [`String`], the empty outputs and the [`Output::Map`]s of
+//! [`String`]s, saved as JSON objects, can be restored. A node whose output is of another type
+//! is recorded as not restorable and runs again.
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+use serde_json::{json, Value};
+
+use crate::{connection::information_packet::Content, node::node::NodeName, Output};
+
+/// Write the outputs of the completed nodes to the checkpoint file at `path`.
+pub(crate) fn save(path: &Path, completed: Vec<(NodeName, Output)>) -> io::Result<()> {
+    let entries: Vec<Value> = completed
+        .into_iter()
+        .map(|(name, output)| match output {
+            Output::Map(map) => match strings(&map) {
+                Some(map) => json!({"name": name, "restorable": true, "output": map}),
+                None => json!({"name": name, "restorable": false}),
+            },
+            output => match output.get_out() {
+                None => json!({"name": name, "restorable": true, "output": null}),
+                Some(content) => match content.get::<String>() {
+                    Some(s) => json!({"name": name, "restorable": true, "output": s}),
+                    None => json!({"name": name, "restorable": false}),
+                },
+            },
+        })
+        .collect();
+    fs::write(path, serde_json::to_string_pretty(&entries)?)
+}
+
+/// Read the restorable outputs from the checkpoint file at `path`.
+pub(crate) fn load(path: &Path) -> io::Result<HashMap<NodeName, Output>> {
+    let entries: Vec<Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let mut restored = HashMap::new();
+    for entry in entries {
+        let name = entry["name"]
+            .as_str()
+            .ok_or_else(|| invalid(format!("missing node name in {}", entry)))?;
+        if entry["restorable"].as_bool() != Some(true) {
+            continue;
+        }
+        let output = match &entry["output"] {
+            Value::Null => Output::empty(),
+            Value::String(s) => Output::Out(Some(Content::new(s.clone()))),
+            Value::Object(map) => {
+                let mut contents = HashMap::new();
+                for (key, value) in map {
+                    let s = value
+                        .as_str()
+                        .ok_or_else(|| invalid(format!("invalid output of node [{}]", name)))?;
+                    contents.insert(key.clone(), Content::new(s.to_string()));
+                }
+                Output::Map(contents)
+            }
+            _ => return Err(invalid(format!("invalid output of node [{}]", name))),
+        };
+        restored.insert(name.to_string(), output);
+    }
+    Ok(restored)
+}
+
+/// Get the named contents of an [`Output::Map`] as strings, `None` if some are not.
+fn strings(map: &HashMap<String, Content>) -> Option<serde_json::Map<String, Value>> {
+    map.iter()
+        .map(|(key, content)| Some((key.clone(), Value::from(content.get::<String>()?.as_str()))))
+        .collect()
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
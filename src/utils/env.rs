@@ -1,4 +1,8 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, RwLock},
+};
 
 use crate::{
     connection::information_packet::Content,
@@ -7,6 +11,9 @@ use crate::{
 
 pub type Variable = Content;
 
+/// Variables layered over an [`EnvVar`] with [`EnvVar::with_overlay`], by their names.
+pub type EnvVarOverlay = HashMap<String, Variable>;
+
 /// # Environment variable.
 ///
 /// When multiple nodes are running, they may need to share the same data or read
@@ -17,11 +24,32 @@ pub type Variable = Content;
 ///
 /// Variables that [`EnvVar`] should have:
 /// - [NodeTable] : a mapping from node's name to `NodeId`.
-/// During the runtime of a `Graph`, [`NodeTable`] allows
-/// each `Node` to look up the id of a specific node by its name.
+///   During the runtime of a `Graph`, [`NodeTable`] allows
+///   each `Node` to look up the id of a specific node by its name.
+///
+/// Nodes can also publish variables while the graph runs with [`EnvVar::set_shared`].
+/// Cloning an [`EnvVar`] copies the variables set with [`EnvVar::set`], but not the
+/// published ones: the clone starts with none.
 #[derive(Debug)]
 pub struct EnvVar {
     variables: HashMap<String, Variable>,
+    /// The variables set while running, shared by the views from [`EnvVar::with_overlay`].
+    shared: Arc<RwLock<HashMap<String, Variable>>>,
+    /// The [`EnvVar`] this one is layered over, consulted for the variables it does not set.
+    base: Option<Arc<EnvVar>>,
+}
+
+impl Clone for EnvVar {
+    /// Copy the variables set with [`EnvVar::set`] and the layers below. The variables
+    /// published with [`EnvVar::set_shared`] are not copied nor shared: the clone starts with
+    /// none, and the variables it publishes are not seen by `self`, nor the other way round.
+    fn clone(&self) -> Self {
+        Self {
+            variables: self.variables.clone(),
+            shared: Arc::default(),
+            base: self.base.clone(),
+        }
+    }
 }
 
 impl EnvVar {
@@ -29,11 +57,105 @@ impl EnvVar {
     pub fn new(node_table: NodeTable) -> Self {
         let mut env = Self {
             variables: HashMap::default(),
+            shared: Arc::default(),
+            base: None,
         };
         env.set(NODE_TABLE_STR, node_table);
         env
     }
 
+    /// Allocate a new [`EnvVar`] holding the OS environment variables whose keys start with
+    /// `prefix`, see [`EnvVar::merge_os_env`]. The [`NodeTable`] of the new [`EnvVar`] is
+    /// empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dagrs::EnvVar;
+    ///
+    /// std::env::set_var("MYAPP_LOG_LEVEL", "debug");
+    /// let env = EnvVar::from_env_prefix("MYAPP_");
+    /// assert_eq!(env.get::<String>("log_level").unwrap(), "debug");
+    /// ```
+    pub fn from_env_prefix(prefix: &str) -> Self {
+        let mut env = Self::new(NodeTable::default());
+        env.merge_os_env(prefix);
+        env
+    }
+
+    /// Add the OS environment variables whose keys start with `prefix`, as [`String`]
+    /// values. The keys are stored without the prefix, in lowercase. The variables already
+    /// set are kept, and the variables whose key or value is not valid Unicode are skipped.
+    pub fn merge_os_env(&mut self, prefix: &str) {
+        for (key, value) in std::env::vars_os() {
+            let (Some(key), Some(value)) = (key.to_str(), value.to_str()) else {
+                log::warn!("Skip the non-Unicode OS environment variable {:?}.", key);
+                continue;
+            };
+            if let Some(name) = key.strip_prefix(prefix) {
+                self.variables
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| Variable::new(value.to_string()));
+            }
+        }
+    }
+
+    /// Get a view of these variables, where the variables of `overlay` shadow the ones of the
+    /// same names. The variables of `self` are not copied, and the view shares the variables
+    /// published with [`EnvVar::set_shared`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use dagrs::{EnvVar, EnvVarOverlay, NodeTable, Variable};
+    ///
+    /// let mut env = EnvVar::new(NodeTable::default());
+    /// env.set("factor", 1usize);
+    /// env.set("scale", 10usize);
+    /// let overlay = EnvVarOverlay::from([("factor".to_string(), Variable::new(5usize))]);
+    /// let view = Arc::new(env).with_overlay(&overlay);
+    /// assert_eq!(view.get::<usize>("factor"), Some(5));
+    /// assert_eq!(view.get::<usize>("scale"), Some(10));
+    /// ```
+    pub fn with_overlay(self: &Arc<Self>, overlay: &EnvVarOverlay) -> Self {
+        Self {
+            variables: overlay.clone(),
+            shared: self.shared.clone(),
+            base: Some(self.clone()),
+        }
+    }
+
+    /// Take the variables, including the ones of the layers below, without the [`NodeTable`].
+    pub(crate) fn into_variables(mut self) -> HashMap<String, Variable> {
+        let mut variables = match self.base.take() {
+            Some(base) => Arc::unwrap_or_clone(base).into_variables(),
+            None => HashMap::new(),
+        };
+        self.variables.remove(NODE_TABLE_STR);
+        variables.extend(self.variables);
+        variables
+    }
+
+    /// Whether the variable named `name` is set or published.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.variable(name).is_some() || self.shared.read().unwrap().contains_key(name)
+    }
+
+    /// Find the variable named `name`, falling back to the published variables.
+    fn lookup(&self, name: &str) -> Option<Variable> {
+        match self.variable(name) {
+            Some(var) => Some(var.clone()),
+            None => self.shared.read().unwrap().get(name).cloned(),
+        }
+    }
+
+    /// Find the variable named `name`, in this layer first.
+    fn variable(&self, name: &str) -> Option<&Variable> {
+        match self.variables.get(name) {
+            Some(var) => Some(var),
+            None => self.base.as_ref()?.variable(name),
+        }
+    }
+
     #[allow(unused)]
     /// Set a global variables.
     ///
@@ -49,19 +171,143 @@ impl EnvVar {
         self.variables.insert(name.to_owned(), v);
     }
 
-    /// Get environment variables through keys of type &str.
+    /// Publish a variable while the graph runs, e.g. a path or a token computed by a node
+    /// for the nodes after it. Unlike [`EnvVar::set`], it only needs a shared reference, so
+    /// an action can call it on the environment it is given.
+    ///
+    /// The variable is visible to [`EnvVar::get`] as soon as it is set, in all the nodes of
+    /// the graph, unless a variable with the same name is set with [`EnvVar::set`]. There is
+    /// no ordering guarantee beyond the dependencies of the nodes: a node only sees the
+    /// variables published by the nodes it waits for.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dagrs::{EnvVar, NodeTable};
+    ///
+    /// let env = EnvVar::new(NodeTable::default());
+    /// env.set_shared("token", "secret".to_string());
+    /// assert_eq!(env.get::<String>("token").unwrap(), "secret");
+    /// ```
+    pub fn set_shared<H: Send + Sync + 'static>(&self, name: &str, var: H) {
+        self.shared
+            .write()
+            .unwrap()
+            .insert(name.to_owned(), Variable::new(var));
+    }
+
+    /// Publish the variable computed by `f` from the one published with the same name, under
+    /// a single write lock, so that concurrent nodes can update it without losing updates.
+    /// `f` gets `None` if no variable of type `H` is published with that name.
     ///
-    /// Note: This method will clone the value. To avoid cloning, use `get_ref`.
+    /// # Example
+    /// ```rust
+    /// use dagrs::{EnvVar, NodeTable};
+    ///
+    /// let env = EnvVar::new(NodeTable::default());
+    /// env.update_shared("count", |count: Option<&usize>| count.map_or(1, |c| c + 1));
+    /// env.update_shared("count", |count: Option<&usize>| count.map_or(1, |c| c + 1));
+    /// assert_eq!(env.get::<usize>("count"), Some(2));
+    /// ```
+    pub fn update_shared<H: Send + Sync + 'static>(
+        &self,
+        name: &str,
+        f: impl FnOnce(Option<&H>) -> H,
+    ) {
+        let mut shared = self.shared.write().unwrap();
+        let var = f(shared.get(name).and_then(|var| var.get()));
+        shared.insert(name.to_owned(), Variable::new(var));
+    }
+
+    /// Remove a variable published with [`EnvVar::set_shared`], from all the views of this
+    /// [`EnvVar`]. Returns whether it was published. The variables set with [`EnvVar::set`]
+    /// are kept.
+    pub fn remove(&self, name: &str) -> bool {
+        self.shared.write().unwrap().remove(name).is_some()
+    }
+
+    /// Get environment variables through keys of type &str, falling back to the variables
+    /// published with [`EnvVar::set_shared`]. The variables set with [`EnvVar::set`] are
+    /// read without locking.
+    ///
+    /// Note: This method will clone the value. To avoid cloning, use [`EnvVar::get_arc`].
     pub fn get<H: Send + Sync + Clone + 'static>(&self, name: &str) -> Option<H> {
-        self.get_ref(name).cloned()
+        self.lookup(name)?.try_clone_as()
+    }
+
+    /// Get environment variables through keys of type &str, falling back to the variables
+    /// published with [`EnvVar::set_shared`], without cloning the value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dagrs::{EnvVar, NodeTable};
+    ///
+    /// let env = EnvVar::new(NodeTable::default());
+    /// env.set_shared("token", "secret".to_string());
+    /// assert_eq!(*env.get_arc::<String>("token").unwrap(), "secret");
+    /// ```
+    pub fn get_arc<H: Send + Sync + 'static>(&self, name: &str) -> Option<Arc<H>> {
+        self.lookup(name)?.into_inner()
     }
 
-    /// Get environment variables through keys of type &str.
+    /// Get environment variables through keys of type &str, by reference.
+    ///
+    /// Only the variables set with [`EnvVar::set`] can be borrowed, as the published ones may
+    /// be replaced while the reference is held. Use [`EnvVar::get_arc`] to see them too.
     pub fn get_ref<H: Send + Sync + 'static>(&self, name: &str) -> Option<&H> {
-        if let Some(content) = self.variables.get(name) {
+        if let Some(content) = self.variable(name) {
             content.get()
         } else {
             None
         }
     }
+
+    /// Get an environment variable, or the default value of its type if it is neither set
+    /// nor published, or has another type.
+    pub fn get_or_default<H: Send + Sync + Clone + Default + 'static>(&self, name: &str) -> H {
+        self.get(name).unwrap_or_default()
+    }
+
+    /// Get an environment variable, or an [`EnvVarError`] if it is neither set nor
+    /// published, or has another type, e.g. to fail a node with a helpful message.
+    ///
+    /// # Example
+    /// ```rust
+    /// use dagrs::{EnvVar, EnvVarError, NodeTable};
+    ///
+    /// # let mut env = EnvVar::new(NodeTable::default());
+    /// env.set("Hello", "World".to_string());
+    /// assert_eq!(*env.require::<String>("Hello").unwrap(), "World");
+    /// assert!(matches!(env.require::<i32>("Hello"), Err(EnvVarError::WrongType(_))));
+    /// assert!(matches!(env.require::<i32>("Bye"), Err(EnvVarError::Missing(_))));
+    /// ```
+    pub fn require<H: Send + Sync + 'static>(&self, name: &str) -> Result<Arc<H>, EnvVarError> {
+        let content = self
+            .lookup(name)
+            .ok_or_else(|| EnvVarError::Missing(name.to_string()))?;
+        content
+            .into_inner()
+            .ok_or_else(|| EnvVarError::WrongType(name.to_string()))
+    }
+}
+
+/// Errors raised by [`EnvVar::require`].
+/// - Missing(name): no environment variable is set with the given name.
+/// - WrongType(name): the environment variable with the given name has another type.
+#[derive(Debug)]
+pub enum EnvVarError {
+    Missing(String),
+    WrongType(String),
 }
+
+impl Display for EnvVarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvVarError::Missing(name) => write!(f, "Environment variable [{}] is not set.", name),
+            EnvVarError::WrongType(name) => {
+                write!(f, "Environment variable [{}] has another type.", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvVarError {}
@@ -1,9 +1,14 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
-use super::output::Output;
+use tokio::sync::watch;
+
+use super::{output::Output, state::NodeState};
 use crate::connection::information_packet::Content;
 
 #[derive(Debug)]
@@ -12,6 +17,19 @@ pub(crate) struct ExecState {
     success: AtomicBool,
     /// Output produced by a task.
     output: Arc<Mutex<Output>>,
+    /// Whether the task has started running. Lazy predecessors wait on it.
+    started: watch::Sender<bool>,
+    /// Whether the task has finished running. Conditional successors wait on it.
+    finished: watch::Sender<bool>,
+    /// The number of attempts made to run the task.
+    attempts: AtomicU32,
+    /// Whether the last attempt to run the task timed out.
+    timed_out: AtomicBool,
+    /// When the task started running, and how long its attempts ran in total.
+    /// `None` if it never ran.
+    timing: Mutex<Option<(Instant, Duration)>>,
+    /// The [`NodeState`] of the task, polled while the graph is running.
+    state: AtomicU8,
     /*/// The semaphore is used to control the synchronous blocking of subsequent tasks to obtain the
     /// execution results of this task.
     /// When a task is successfully executed, the permits inside the semaphore will be increased to
@@ -29,6 +47,12 @@ impl ExecState {
         Self {
             success: AtomicBool::new(false),
             output: Arc::new(Mutex::new(Output::empty())),
+            started: watch::Sender::new(false),
+            finished: watch::Sender::new(false),
+            attempts: AtomicU32::new(0),
+            timed_out: AtomicBool::new(false),
+            timing: Mutex::new(None),
+            state: AtomicU8::new(NodeState::Pending as u8),
             //semaphore: Semaphore::new(0),
         }
     }
@@ -62,6 +86,93 @@ impl ExecState {
         self.success.store(false, Ordering::Relaxed)
     }
 
+    pub(crate) fn set_attempts(&self, attempts: u32) {
+        self.attempts.store(attempts, Ordering::Relaxed)
+    }
+
+    pub(crate) fn attempts(&self) -> u32 {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_timed_out(&self, timed_out: bool) {
+        self.timed_out.store(timed_out, Ordering::Relaxed)
+    }
+
+    /// The last attempt to run the task timed out or not.
+    pub(crate) fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_timing(&self, start: Instant, duration: Duration) {
+        *self.timing.lock().unwrap() = Some((start, duration));
+    }
+
+    /// How long the task took to run, `None` if it never ran.
+    pub(crate) fn duration(&self) -> Option<Duration> {
+        self.timing.lock().unwrap().map(|(_, duration)| duration)
+    }
+
+    /// When the task started running and how long it took, `None` if it never ran.
+    pub(crate) fn timing(&self) -> Option<(Instant, Duration)> {
+        *self.timing.lock().unwrap()
+    }
+
+    pub(crate) fn set_state(&self, state: NodeState) {
+        self.state.store(state as u8, Ordering::Release)
+    }
+
+    pub(crate) fn state(&self) -> NodeState {
+        NodeState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn mark_skipped(&self) {
+        self.set_state(NodeState::Skipped)
+    }
+
+    /// The task was skipped without running or not.
+    pub(crate) fn skipped(&self) -> bool {
+        self.state() == NodeState::Skipped
+    }
+
+    pub(crate) fn mark_cancelled(&self) {
+        self.set_state(NodeState::Cancelled)
+    }
+
+    /// The task was cancelled because the graph was aborted or not.
+    pub(crate) fn cancelled(&self) -> bool {
+        self.state() == NodeState::Cancelled
+    }
+
+    /// Mark the task as started, waking up all the tasks waiting on it.
+    pub(crate) fn mark_started(&self) {
+        self.started.send_replace(true);
+    }
+
+    /// Wait until the task has started running.
+    pub(crate) async fn wait_started(&self) {
+        let _ = self.started.subscribe().wait_for(|started| *started).await;
+    }
+
+    /// Mark the task as finished with the given output, waking up all the tasks waiting on it.
+    pub(crate) fn finish(&self, output: Output) {
+        *self.output.lock().unwrap() = output;
+        self.mark_finished();
+    }
+
+    /// Mark the task as finished, keeping its output.
+    pub(crate) fn mark_finished(&self) {
+        self.finished.send_replace(true);
+    }
+
+    /// Wait until the task has finished running.
+    pub(crate) async fn wait_finished(&self) {
+        let _ = self
+            .finished
+            .subscribe()
+            .wait_for(|finished| *finished)
+            .await;
+    }
+
     /*/// The semaphore is used to control the synchronous acquisition of task output results.
     /// Under normal circumstances, first use the semaphore to obtain a permit, and then call
     /// the `get_output` function to obtain the output. If the current task is not completed
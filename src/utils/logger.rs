@@ -0,0 +1,212 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use log::Level;
+
+use crate::node::node::{NodeId, NodeName};
+
+tokio::task_local! {
+    static LOGGER: NodeLogger;
+}
+
+/// A log record emitted by a node through its [`NodeLogger`].
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub node_id: NodeId,
+    pub node_name: NodeName,
+    pub level: Level,
+    pub message: String,
+}
+
+/// # Log sink
+///
+/// A [`LogSink`] receives the records logged by the nodes of a `Graph`, e.g. to route them
+/// to a file per node. It is set with `Graph::set_log_sink`. Without a sink, the records go
+/// to the global logger.
+pub trait LogSink: Send + Sync {
+    fn log(&self, record: &LogRecord);
+}
+
+/// # Node logger
+///
+/// A [`NodeLogger`] logs records carrying the id and the name of the node running, to the
+/// log sink of its graph. It is got with [`NodeLogger::current`] inside an action.
+///
+/// # Example
+/// ```rust
+/// use dagrs::{async_trait::async_trait, Action, EnvVar, InChannels, NodeLogger, OutChannels, Output};
+/// use std::sync::Arc;
+///
+/// struct Hello;
+///
+/// #[async_trait]
+/// impl Action for Hello {
+///     async fn run(&self, _: &mut InChannels, _: &OutChannels, _: Arc<EnvVar>) -> Output {
+///         if let Some(logger) = NodeLogger::current() {
+///             logger.info("Hello");
+///         }
+///         Output::empty()
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct NodeLogger {
+    id: NodeId,
+    name: NodeName,
+    sink: Option<Arc<dyn LogSink>>,
+}
+
+impl NodeLogger {
+    pub(crate) fn new(id: NodeId, name: NodeName, sink: Option<Arc<dyn LogSink>>) -> Self {
+        Self { id, name, sink }
+    }
+
+    /// Get the logger of the node running in the current task, `None` outside of a node.
+    pub fn current() -> Option<NodeLogger> {
+        LOGGER.try_with(|logger| logger.clone()).ok()
+    }
+
+    /// Run `fut` with this logger as the logger of the current node.
+    pub(crate) async fn scope<F: std::future::Future>(self, fut: F) -> F::Output {
+        LOGGER.scope(self, fut).await
+    }
+
+    /// Log `message` at `level`, to the log sink of the graph if any.
+    pub fn log(&self, level: Level, message: impl Into<String>) {
+        let record = LogRecord {
+            node_id: self.id,
+            node_name: self.name.clone(),
+            level,
+            message: message.into(),
+        };
+        match &self.sink {
+            Some(sink) => sink.log(&record),
+            None => log::log!(
+                level,
+                "[name: {}, id: {}] {}",
+                record.node_name,
+                record.node_id.0,
+                record.message
+            ),
+        }
+    }
+
+    pub fn error(&self, message: impl Into<String>) {
+        self.log(Level::Error, message)
+    }
+
+    pub fn warn(&self, message: impl Into<String>) {
+        self.log(Level::Warn, message)
+    }
+
+    pub fn info(&self, message: impl Into<String>) {
+        self.log(Level::Info, message)
+    }
+
+    pub fn debug(&self, message: impl Into<String>) {
+        self.log(Level::Debug, message)
+    }
+}
+
+/// A [`LogSink`] keeping the records in memory.
+#[derive(Debug, Default)]
+pub struct MemoryLogSink {
+    records: Mutex<Vec<LogRecord>>,
+}
+
+impl MemoryLogSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the records logged so far, in order.
+    pub fn records(&self) -> Vec<LogRecord> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+impl LogSink for MemoryLogSink {
+    fn log(&self, record: &LogRecord) {
+        self.records.lock().unwrap().push(record.clone());
+    }
+}
+
+/// A [`LogSink`] appending the records of each node to the file `<name>.log` in a directory.
+///
+/// The characters of the name other than ASCII letters, digits, `-` and `_` are replaced
+/// with `_` in the file name, so that a node cannot write outside the directory.
+#[derive(Debug)]
+pub struct FileLogSink {
+    dir: PathBuf,
+}
+
+impl FileLogSink {
+    /// Log to the files in `dir`, which is created if missing.
+    pub fn new(dir: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+}
+
+/// Get the file stem for the log of the node named `name`.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "_".to_string()
+    } else {
+        stem
+    }
+}
+
+impl LogSink for FileLogSink {
+    fn log(&self, record: &LogRecord) {
+        let path = self
+            .dir
+            .join(format!("{}.log", file_stem(&record.node_name)));
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| {
+                writeln!(
+                    file,
+                    "{} [id: {}] {}",
+                    record.level, record.node_id.0, record.message
+                )
+            });
+        if let Err(e) = written {
+            log::warn!(
+                "Failed to write the log of node [{}]: {}",
+                record.node_name,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::file_stem;
+
+    #[test]
+    fn sanitized_file_stem() {
+        assert_eq!(file_stem("Node X"), "Node_X");
+        assert_eq!(file_stem("../../etc/passwd"), "______etc_passwd");
+        assert_eq!(file_stem("/tmp/a"), "_tmp_a");
+        assert_eq!(file_stem(""), "_");
+    }
+}
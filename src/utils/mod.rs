@@ -1,3 +1,13 @@
+pub mod aggregator;
+pub mod cache;
+pub(crate) mod checkpoint;
 pub mod env;
 pub mod execstate;
+pub mod logger;
 pub mod output;
+pub(crate) mod panic;
+pub mod progress;
+pub(crate) mod rate;
+#[cfg(all(unix, feature = "signal"))]
+pub(crate) mod signal;
+pub mod state;
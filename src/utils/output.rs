@@ -22,15 +22,32 @@
 //! use dagrs::Output;
 //! use dagrs::Content;
 //! let err_out = Output::Err("some error messages!".to_string());
+//! ```
+//!
+//! A Node producing several results for different successors can output them by names,
+//! and each successor picks the one it needs with `Content::get_named`:
+//!
+//! ```rust
+//! use dagrs::{Content, Output};
+//! let out = Output::map([("count", Content::new(3usize)), ("label", Content::new("ok"))]);
+//! assert_eq!(out.get_named::<usize>("count"), Some(&3));
+//! ```
 
-use crate::connection::information_packet::Content;
+use std::collections::HashMap;
+
+use crate::connection::information_packet::{Content, ContentSize};
 
 /// [`Output`] represents the output of a node. Different from information packet (`Content`,
 /// used to communicate with other Nodes), `Output` carries the information that `Node`
 /// needs to pass to the `Graph`.
+///
+/// [`Output::Map`] carries several named contents. Its content, e.g. got with
+/// `Graph::get_result`, is the whole `HashMap<String, Content>`, whose size is the sum of the
+/// sizes of the named contents of known size.
 #[derive(Clone, Debug)]
 pub enum Output {
     Out(Option<Content>),
+    Map(HashMap<String, Content>),
     Err(String),
     ErrWithExitCode(Option<i32>, Option<Content>),
 }
@@ -44,6 +61,21 @@ impl Output {
         Self::Out(Some(Content::new(val)))
     }
 
+    /// Construct a new [`Output`] whose size is known. See [`Content::sized`].
+    pub fn sized<H: ContentSize + Send + Sync + 'static>(val: H) -> Self {
+        Self::Out(Some(Content::sized(val)))
+    }
+
+    /// Construct an [`Output::Map`] from named contents.
+    pub fn map<K: Into<String>>(outputs: impl IntoIterator<Item = (K, Content)>) -> Self {
+        Self::Map(
+            outputs
+                .into_iter()
+                .map(|(key, content)| (key.into(), content))
+                .collect(),
+        )
+    }
+
     /// Construct an empty [`Output`].
     pub fn empty() -> Self {
         Self::Out(None)
@@ -63,7 +95,7 @@ impl Output {
     pub(crate) fn is_err(&self) -> bool {
         match self {
             Self::Err(_) | Self::ErrWithExitCode(_, _) => true,
-            Self::Out(_) => false,
+            Self::Out(_) | Self::Map(_) => false,
         }
     }
 
@@ -71,14 +103,36 @@ impl Output {
     pub(crate) fn get_out(&self) -> Option<Content> {
         match self {
             Self::Out(ref out) => out.clone(),
+            Self::Map(map) => {
+                let size = map.values().filter_map(Content::size).reduce(|a, b| a + b);
+                Some(Content::with_size(map.clone(), size))
+            }
             Self::Err(_) | Self::ErrWithExitCode(_, _) => None,
         }
     }
 
+    /// Construct the [`Output`] whose content is `content`, e.g. stored in a cache. The map
+    /// of named contents of an [`Output::Map`] gives the map back.
+    pub(crate) fn from_content(content: Content) -> Self {
+        match content.get::<HashMap<String, Content>>() {
+            Some(map) => Self::Map(map.clone()),
+            None => Self::Out(Some(content)),
+        }
+    }
+
+    /// Get the content named `key` of an [`Output::Map`], `None` if there is no such content
+    /// of type `H` or the output is not a map.
+    pub fn get_named<H: 'static>(&self, key: &str) -> Option<&H> {
+        match self {
+            Self::Map(map) => map.get(key)?.get(),
+            Self::Out(_) | Self::Err(_) | Self::ErrWithExitCode(_, _) => None,
+        }
+    }
+
     /// Get error information stored in [`Output`].
     pub(crate) fn get_err(&self) -> Option<String> {
         match self {
-            Self::Out(_) => None,
+            Self::Out(_) | Self::Map(_) => None,
             Self::Err(err) => Some(err.to_string()),
             Self::ErrWithExitCode(code, _) => {
                 let error_code = code.map_or("".to_string(), |v| v.to_string());
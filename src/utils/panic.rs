@@ -0,0 +1,106 @@
+//! Quiet panics
+//!
+//! By default, a panic inside a node is printed by the panic hook of the process before
+//! the graph records it as a failure of the node. [`QuietPanic`] wraps the future of a
+//! node, so that the panics raised while polling it are not printed by the hook.
+//!
+//! [`CatchPanic`] wraps the future of a node to catch its panics, so that they can be
+//! recorded whichever executor polls it.
+
+use std::{
+    any::Any,
+    cell::Cell,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::Once,
+    task::{Context, Poll},
+};
+
+thread_local! {
+    /// Whether the future being polled on this thread should panic quietly.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Install a panic hook that skips the previous hook for quiet panics. The hook is
+/// installed only once for the whole process.
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(|quiet| quiet.get()) {
+                previous(info)
+            }
+        }));
+    });
+}
+
+/// A future whose panics are not printed by the panic hook.
+pub(crate) struct QuietPanic<F> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> QuietPanic<F> {
+    pub(crate) fn new(inner: F) -> Self {
+        install_hook();
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+}
+
+/// Restore the quiet flag of the thread when dropped, even during unwinding.
+struct QuietGuard(bool);
+
+impl Drop for QuietGuard {
+    fn drop(&mut self) {
+        QUIET.with(|quiet| quiet.set(self.0));
+    }
+}
+
+impl<F: Future> Future for QuietPanic<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let _guard = QuietGuard(QUIET.with(|quiet| quiet.replace(true)));
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+/// A future whose panics are caught, and returned as the payloads of the panics.
+pub(crate) struct CatchPanic<F> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> CatchPanic<F> {
+    pub(crate) fn new(inner: F) -> Self {
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+}
+
+impl<F: Future> Future for CatchPanic<F> {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match panic::catch_unwind(AssertUnwindSafe(|| self.inner.as_mut().poll(cx))) {
+            Ok(Poll::Ready(out)) => Poll::Ready(Ok(out)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// Get the message carried by a panic payload.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// # Graph progress
+///
+/// [`Progress`] counts the nodes of a `Graph` resolved in the current run, whether they
+/// succeed or fail. It is a cheap handle that can be cloned out of the graph (see
+/// `Graph::progress_handle`) and polled from another task while the graph is running.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    inner: Arc<Counter>,
+}
+
+#[derive(Debug, Default)]
+struct Counter {
+    resolved: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl Progress {
+    /// The fraction of resolved nodes, from 0.0 to 1.0. A graph that has not started or has
+    /// no nodes makes no progress.
+    pub fn fraction(&self) -> f32 {
+        let total = self.inner.total.load(Ordering::Acquire);
+        if total == 0 {
+            return 0.0;
+        }
+        self.inner.resolved.load(Ordering::Acquire) as f32 / total as f32
+    }
+
+    /// Start counting a run of `total` nodes.
+    pub(crate) fn begin(&self, total: usize) {
+        self.inner.resolved.store(0, Ordering::Release);
+        self.inner.total.store(total, Ordering::Release);
+    }
+
+    /// Get a guard that counts a node as resolved when dropped, even if the node panics.
+    pub(crate) fn resolve_on_drop(&self) -> Resolved {
+        Resolved(self.clone())
+    }
+}
+
+/// See [`Progress::resolve_on_drop`].
+pub(crate) struct Resolved(Progress);
+
+impl Drop for Resolved {
+    fn drop(&mut self) {
+        self.0.inner.resolved.fetch_add(1, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,41 @@
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+/// Limits the starts to `rate` within any period of length `per`, see `Graph::set_start_rate`.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    rate: usize,
+    per: Duration,
+    /// The times of the last starts, at most `rate` of them, in order.
+    starts: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(rate: u32, per: Duration) -> Self {
+        Self {
+            rate: rate as usize,
+            per,
+            starts: Mutex::new(VecDeque::with_capacity(rate as usize)),
+        }
+    }
+
+    /// Wait until a start is allowed. The start is reserved before waiting, so the waiting
+    /// callers start in the order they called.
+    pub(crate) async fn acquire(&self) {
+        let start = {
+            let mut starts = self.starts.lock().unwrap();
+            let now = Instant::now();
+            let start = if starts.len() < self.rate {
+                now
+            } else {
+                starts
+                    .pop_front()
+                    .map_or(now, |first| now.max(first + self.per))
+            };
+            starts.push_back(start);
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
@@ -0,0 +1,138 @@
+use std::{future::poll_fn, io, mem, ptr, sync::Mutex, task::Poll};
+
+use log::warn;
+
+use tokio::{
+    runtime::Handle,
+    signal::unix::{signal, Signal, SignalKind},
+};
+
+/// The signals stopping a run.
+const SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+/// The dispositions of [`SIGNALS`] swapped in and out by the runs listening to them.
+struct Dispositions {
+    /// The number of runs listening.
+    listening: usize,
+    /// The dispositions before the first run listening, restored once no run listens.
+    previous: Option<[libc::sigaction; 2]>,
+    /// The handlers of tokio, which register them only once per process.
+    tokio: Option<[libc::sigaction; 2]>,
+}
+
+static DISPOSITIONS: Mutex<Dispositions> = Mutex::new(Dispositions {
+    listening: 0,
+    previous: None,
+    tokio: None,
+});
+
+/// Get the current dispositions of [`SIGNALS`].
+fn current() -> io::Result<[libc::sigaction; 2]> {
+    // SAFETY: `sigaction` is a plain C struct, for which all zero bytes are a valid value.
+    let mut actions: [libc::sigaction; 2] = unsafe { mem::zeroed() };
+    for (signal, action) in SIGNALS.into_iter().zip(actions.iter_mut()) {
+        // SAFETY: `action` is valid for writes during the call, and a null new action only
+        // queries the disposition without changing it.
+        if unsafe { libc::sigaction(signal, ptr::null(), action) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(actions)
+}
+
+/// Set the dispositions of [`SIGNALS`].
+fn set(actions: &[libc::sigaction; 2]) -> io::Result<()> {
+    for (signal, action) in SIGNALS.into_iter().zip(actions) {
+        // SAFETY: `action` was filled in by `sigaction` in [`current`] and is valid for reads
+        // during the call, and a null old action is allowed when it is not needed.
+        if unsafe { libc::sigaction(signal, action, ptr::null_mut()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Restore the dispositions of [`SIGNALS`] from before the first run listening, if any.
+fn restore(dispositions: &mut Dispositions) {
+    if let Some(previous) = dispositions.previous.take() {
+        if let Err(e) = set(&previous) {
+            warn!(
+                "Failed to restore the dispositions of SIGINT and SIGTERM: {}",
+                e
+            );
+        }
+    }
+}
+
+/// Start listening to SIGINT and SIGTERM for a run. The signals are received by the driver of
+/// the runtime of `handle`, which must have IO enabled.
+///
+/// The dispositions the signals had before are restored once the last guard is dropped, so
+/// that a signal outside of a run terminates the process as usual.
+pub(crate) fn listen(handle: &Handle) -> io::Result<(Listener, SignalGuard)> {
+    let mut dispositions = DISPOSITIONS.lock().unwrap();
+    if dispositions.listening == 0 {
+        dispositions.previous = Some(current()?);
+        if let Some(tokio) = &dispositions.tokio {
+            if let Err(e) = set(tokio) {
+                restore(&mut dispositions);
+                return Err(e);
+            }
+        }
+    }
+    let _enter = handle.enter();
+    let listener = signal(SignalKind::interrupt()).and_then(|interrupt| {
+        Ok(Listener {
+            interrupt,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    });
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            if dispositions.listening == 0 {
+                restore(&mut dispositions);
+            }
+            return Err(e);
+        }
+    };
+    if dispositions.tokio.is_none() {
+        dispositions.tokio = current().ok();
+    }
+    dispositions.listening += 1;
+    Ok((listener, SignalGuard(())))
+}
+
+/// Receives SIGINT and SIGTERM, see [`listen`].
+pub(crate) struct Listener {
+    interrupt: Signal,
+    terminate: Signal,
+}
+
+impl Listener {
+    /// Wait until SIGINT or SIGTERM is received.
+    pub(crate) async fn recv(&mut self) {
+        poll_fn(|cx| {
+            if self.interrupt.poll_recv(cx).is_ready() || self.terminate.poll_recv(cx).is_ready() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// Restores the dispositions of SIGINT and SIGTERM when the last one is dropped, see
+/// [`listen`].
+pub(crate) struct SignalGuard(());
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        let mut dispositions = DISPOSITIONS.lock().unwrap();
+        dispositions.listening -= 1;
+        if dispositions.listening == 0 {
+            restore(&mut dispositions);
+        }
+    }
+}
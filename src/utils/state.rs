@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use super::execstate::ExecState;
+use crate::node::node::NodeId;
+
+/// # Node state
+///
+/// The state of a node in the current run of a `Graph`, see `Graph::node_states`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeState {
+    /// The node has not been spawned yet.
+    Pending,
+    /// The node is spawned, and waits for its lazy successor to start or for the predecessors
+    /// of its conditional edges to finish.
+    WaitingOnDeps,
+    /// The node is running. A node receiving from its input channels is running while it
+    /// waits for its predecessors to send.
+    Running,
+    /// The node completed successfully.
+    Succeeded,
+    /// The node failed or panicked.
+    Failed,
+    /// The node was cancelled because the graph was aborted.
+    Cancelled,
+    /// The node was skipped without running.
+    Skipped,
+}
+
+impl NodeState {
+    pub(crate) fn from_u8(state: u8) -> Self {
+        match state {
+            0 => NodeState::Pending,
+            1 => NodeState::WaitingOnDeps,
+            2 => NodeState::Running,
+            3 => NodeState::Succeeded,
+            4 => NodeState::Failed,
+            5 => NodeState::Cancelled,
+            _ => NodeState::Skipped,
+        }
+    }
+
+    /// Whether the node is done for the current run.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            NodeState::Succeeded | NodeState::Failed | NodeState::Cancelled | NodeState::Skipped
+        )
+    }
+}
+
+/// # Node states
+///
+/// [`NodeStates`] views the states of the nodes of a `Graph` in the current run. It is a
+/// cheap handle that can be cloned out of the graph (see `Graph::node_states_handle`) and
+/// polled from another task while the graph is running, e.g. to draw a dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct NodeStates {
+    inner: Arc<RwLock<HashMap<NodeId, Arc<ExecState>>>>,
+}
+
+impl NodeStates {
+    /// Get the state of every node of the current run. The map is empty before the graph is
+    /// initialized.
+    pub fn snapshot(&self) -> HashMap<NodeId, NodeState> {
+        self.inner
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(&id, state)| (id, state.state()))
+            .collect()
+    }
+
+    /// Get the state of the node with the given id, `None` if it is not in the current run.
+    pub fn get(&self, id: &NodeId) -> Option<NodeState> {
+        self.inner
+            .read()
+            .unwrap()
+            .get(id)
+            .map(|state| state.state())
+    }
+
+    /// View the states of a new run.
+    pub(crate) fn begin(&self, states: &HashMap<NodeId, Arc<ExecState>>) {
+        *self.inner.write().unwrap() = states.clone();
+    }
+}